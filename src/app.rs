@@ -1,30 +1,107 @@
-use iced::widget::{button, column as iced_column, text, Column};
+use std::thread;
+use iced::futures::channel::oneshot;
+use iced::widget::{button, column as iced_column, scrollable, text, Column};
+use iced::{Color, Element, Task};
+
+use crate::instruments::player::Instrument;
+use crate::theory::interval::IntervalStep;
+use crate::theory::pitch::Pitch;
 
 pub fn run_app() -> iced::Result {
-    iced::application("Forme", State::update, State::view).run()
+    iced::application("Forme", State::update, State::view).run_with(State::new)
 }
 
 #[derive(Debug, Clone)]
 enum Message {
     Increment,
+    PitchesLoaded(Result<Vec<(Pitch, bool)>, String>),
+}
+
+/// The pitches of the instrument shown in the browser panel.
+#[derive(Default)]
+enum PitchList {
+    #[default]
+    Loading,
+    /// Every pitch in the range of the instrument, with whether it has a sample of its own
+    Loaded(Vec<(Pitch, bool)>),
+    Failed(String),
 }
 
 #[derive(Default)]
 struct State {
     count: u64,
+    instrument: Option<Instrument>,
+    pitches: PitchList,
 }
 
 impl State {
+    fn new() -> (Self, Task<Message>) {
+        let instrument = Instrument::SalamanderGrandPiano;
+        let state = State {
+            instrument: Some(instrument.clone()),
+            ..State::default()
+        };
+        // Reading the sample folder blocks, so it runs on its own thread and the task only awaits the result.
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn(move || {
+            let _ = sender.send(load_pitches(&instrument));
+        });
+        let task = Task::perform(
+            async move { receiver.await.unwrap_or_else(|_| Err("Loading was interrupted".to_string())) },
+            Message::PitchesLoaded,
+        );
+        (state, task)
+    }
+
     fn update(&mut self, message: Message) {
         match message {
             Message::Increment => self.count += 1,
+            Message::PitchesLoaded(Ok(pitches)) => self.pitches = PitchList::Loaded(pitches),
+            Message::PitchesLoaded(Err(error)) => self.pitches = PitchList::Failed(error),
         }
     }
 
-    fn view(&self) -> Column<Message> {
+    fn view(&self) -> Column<'_, Message> {
         Column::with_children(vec![
             text(self.count).into(),
             button("+").on_press(Message::Increment).into(),
+            self.pitch_panel(),
         ])
     }
+
+    /// Lists the pitches of the instrument, graying out the ones that would be pitch-shifted from a neighbouring sample.
+    fn pitch_panel(&self) -> Element<'_, Message> {
+        let title = match &self.instrument {
+            Some(instrument) => format!("Pitches of {}", instrument),
+            None => "Pitches".to_string(),
+        };
+        let body: Element<'_, Message> = match &self.pitches {
+            PitchList::Loading => text("Loading samples...").into(),
+            PitchList::Failed(error) => text(format!("Could not load the samples: {}", error)).into(),
+            PitchList::Loaded(pitches) => {
+                let rows = pitches.iter().map(|(pitch, sampled)| {
+                    let row = text(pitch.to_string());
+                    if *sampled { row.into() } else { row.color(Color::from_rgb(0.6, 0.6, 0.6)).into() }
+                });
+                scrollable(Column::with_children(rows)).into()
+            }
+        };
+        iced_column![text(title), body].into()
+    }
+}
+
+/// Lists every pitch from the lowest to the highest sample of the instrument, with whether it has a sample of its own.
+fn load_pitches(instrument: &Instrument) -> Result<Vec<(Pitch, bool)>, String> {
+    let sampled = instrument.available_pitches().map_err(|error| error.to_string())?;
+    let (lowest, highest) = instrument.range().map_err(|error| error.to_string())?;
+    let half = f32::from(IntervalStep::Half);
+    let mut pitches = Vec::new();
+    let mut value = f32::from(lowest);
+    while value <= f32::from(highest.clone()) {
+        let pitch = Pitch::try_from(value).map_err(|_| "Pitch out of range".to_string())?;
+        let has_sample = sampled.contains(&pitch);
+        pitches.push((pitch, has_sample));
+        value += half;
+    }
+    Ok(pitches)
 }
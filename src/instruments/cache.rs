@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use crate::instruments::player::decode_sample_file;
+
+/// A cache of decoded sample files, so that every file is decoded only once.
+#[derive(Debug, Default)]
+pub struct SampleCache {
+    samples: HashMap<PathBuf, (u32, Vec<f32>)>,
+    decodes: usize,
+}
+
+impl SampleCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the sample rate and the samples of a file, decoding it on first use, see `decode_sample_file`.
+    pub fn get(&mut self, path: &Path) -> Result<&(u32, Vec<f32>), Box<dyn Error>> {
+        if !self.samples.contains_key(path) {
+            let decoded = decode_sample_file(path)?;
+            self.samples.insert(path.to_path_buf(), decoded);
+            self.decodes += 1;
+        }
+        Ok(&self.samples[path])
+    }
+
+    /// The number of files decoded so far.
+    pub fn decodes(&self) -> usize {
+        self.decodes
+    }
+}
+
+#[cfg(test)]
+mod sample_cache_tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_once() {
+        let path = Path::new("./resources/test_samples/piano/C4.flac");
+        let mut cache = SampleCache::new();
+        let (sample_rate, samples) = cache.get(path).unwrap().clone();
+        assert_eq!(sample_rate, 8000);
+        assert_eq!(samples.len(), 2000);
+        cache.get(path).unwrap();
+        assert_eq!(cache.decodes(), 1);
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let mut cache = SampleCache::new();
+        assert!(cache.get(Path::new("./resources/test_samples/piano/missing.flac")).is_err());
+        assert_eq!(cache.decodes(), 0);
+    }
+}
@@ -0,0 +1,442 @@
+use std::time::Duration;
+
+/// What to do with the last frame when the samples do not divide evenly into frames.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum PartialFrame {
+    /// Pad the last frame with silence to the full frame length
+    #[default]
+    Pad,
+    /// Drop the samples that do not fill a whole frame
+    Truncate,
+}
+
+/// The shape of the gain over a fade.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum FadeCurve {
+    /// The gain changes at a constant rate
+    #[default]
+    Linear,
+    /// The gain changes at a constant rate in decibels over a 60 dB range, as loudness is perceived:
+    /// a fade-out drops quickly at first, a fade-in rises slowly at first
+    Exponential,
+    /// The inverse of `Exponential`: a fade-out holds the gain at first and drops quickly at the end
+    Logarithmic,
+}
+
+impl FadeCurve {
+    /// Calculates the gain at a point of a fade-in, from 0.0 at the start (`position` 0.0) to 1.0 at the end (`position` 1.0).
+    ///
+    /// A fade-out uses the gain at `1.0 - position`.
+    pub fn gain(&self, position: f32) -> f32 {
+        let position = position.clamp(0.0, 1.0);
+        match self {
+            FadeCurve::Linear => position,
+            FadeCurve::Exponential => (1000f32.powf(position) - 1.0) / 999.0,
+            FadeCurve::Logarithmic => (1.0 + 999.0 * position).log(1000.0),
+        }
+    }
+}
+
+/// The attack and the release of a sound, see `apply_envelope`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Envelope {
+    /// How long the sound takes to fade in
+    pub attack: Duration,
+    /// How long the sound takes to fade out at its end
+    pub release: Duration,
+    pub curve: FadeCurve,
+}
+
+impl Envelope {
+    /// Shapes samples at the given sample rate with the envelope.
+    pub fn apply(&self, samples: &mut [f32], sample_rate: u32) {
+        let attack = duration_to_samples(self.attack, sample_rate);
+        let release = duration_to_samples(self.release, sample_rate);
+        apply_envelope(samples, attack, release, self.curve.clone());
+    }
+}
+
+/// Converts a duration to a number of samples at the given sample rate.
+pub fn duration_to_samples(duration: Duration, sample_rate: u32) -> usize {
+    (duration.as_secs_f64() * sample_rate as f64).round() as usize
+}
+
+/// Calculates the gain for a note velocity.
+///
+/// The velocity (0.0 to 1.0) is squared, since perceived loudness does not grow linearly with amplitude:
+/// half the velocity plays the note at a quarter of the amplitude.
+pub fn velocity_gain(velocity: f32) -> f32 {
+    velocity.clamp(0.0, 1.0).powi(2)
+}
+
+/// Scales the amplitude of the samples by a gain.
+pub fn apply_gain(samples: &mut [f32], gain: f32) {
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+}
+
+/// Gets the largest absolute amplitude of the samples, 0.0 for no samples.
+///
+/// A peak above 1.0 means the samples clip when exported or played, see `count_clipped`.
+pub fn peak_amplitude(samples: &[f32]) -> f32 {
+    samples.iter().fold(0.0, |peak, sample| f32::max(peak, sample.abs()))
+}
+
+/// Counts the samples outside -1.0..=1.0, e.g. to decide whether a mix needs to be normalized before it is exported.
+pub fn count_clipped(samples: &[f32]) -> usize {
+    samples.iter().filter(|sample| sample.abs() > 1.0).count()
+}
+
+/// Trims the samples to `length` samples.
+///
+/// If the samples are cut, the last `fade` samples are faded out along the curve so the cut does not click.
+/// If the samples are shorter than `length`, they are padded with silence.
+pub fn trim_with_fade(samples: &[f32], length: usize, fade: usize, curve: FadeCurve) -> Vec<f32> {
+    let mut out: Vec<f32> = samples.iter().copied().take(length).collect();
+    if samples.len() > length {
+        let fade = fade.min(length);
+        for i in 0..fade {
+            out[length - fade + i] *= curve.gain(1.0 - (i + 1) as f32 / fade as f32);
+        }
+    }
+    out.resize(length, 0.0);
+    out
+}
+
+/// Shapes the samples with an attack and a release: the first `attack` samples fade in and the last `release` samples fade out
+/// along the curve.
+///
+/// The attack and the release are shortened to the length of the samples; where they overlap both gains apply.
+pub fn apply_envelope(samples: &mut [f32], attack: usize, release: usize, curve: FadeCurve) {
+    let len = samples.len();
+    let attack = attack.min(len);
+    for (i, sample) in samples[..attack].iter_mut().enumerate() {
+        *sample *= curve.gain(i as f32 / attack as f32);
+    }
+    let release = release.min(len);
+    for i in 0..release {
+        samples[len - release + i] *= curve.gain(1.0 - (i + 1) as f32 / release as f32);
+    }
+}
+
+/// Splits the samples into frames of `frame_len` samples, e.g. for an audio backend that is fed fixed-size buffers.
+///
+/// The last frame is padded with silence or dropped when it is not full, see `PartialFrame`.
+/// A frame length of 0 produces no frames.
+pub fn frame_samples(samples: &[f32], frame_len: usize, partial: PartialFrame) -> Vec<Vec<f32>> {
+    if frame_len == 0 {
+        return Vec::new();
+    }
+    let mut frames: Vec<Vec<f32>> = samples.chunks(frame_len).map(|frame| frame.to_vec()).collect();
+    if let Some(last) = frames.last_mut() {
+        if last.len() < frame_len {
+            match partial {
+                PartialFrame::Pad => last.resize(frame_len, 0.0),
+                PartialFrame::Truncate => {
+                    frames.pop();
+                }
+            }
+        }
+    }
+    frames
+}
+
+/// Resamples the samples from one sample rate to another, interpolating linearly between neighbouring samples.
+///
+/// The resampled length keeps the duration of the samples, e.g. 100 samples at 500 Hz become 200 samples at 1000 Hz.
+pub fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let length = (samples.len() as f64 * to_rate as f64 / from_rate as f64).round() as usize;
+    let step = from_rate as f64 / to_rate as f64;
+    (0..length)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position.floor() as usize;
+            let t = (position - index as f64) as f32;
+            let current = samples[index.min(samples.len() - 1)];
+            let next = samples[(index + 1).min(samples.len() - 1)];
+            current * (1.0 - t) + next * t
+        })
+        .collect()
+}
+
+/// Generates white noise in -1.0..1.0.
+///
+/// The noise comes from a xorshift generator, so the same seed always gives the same samples.
+pub fn white_noise(length: usize, seed: u32) -> Vec<f32> {
+    // xorshift gets stuck at 0
+    let mut state = seed.max(1);
+    (0..length)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f32 / u32::MAX as f32 * 2.0 - 1.0
+        })
+        .collect()
+}
+
+/// Finds a stable region of a sample that can be looped to sustain it.
+///
+/// The attack at the start and the release at the end of a recorded note are not stable,
+/// so the region spans the middle half of the sample.
+///
+/// # Returns
+///
+/// A tuple of the start (inclusive) and the end (exclusive) of the region.
+pub fn find_loop_region(samples: &[f32]) -> (usize, usize) {
+    (samples.len() / 4, samples.len() * 3 / 4)
+}
+
+/// Sustains a sample to `length` samples by repeating its loop region.
+///
+/// The end of the loop is crossfaded with the `crossfade` samples right before the loop start,
+/// so jumping back from the loop end to the loop start does not click.
+///
+/// # Arguments
+/// * `samples` - The sample to sustain
+/// * `loop_start` - The start (inclusive) of the loop region
+/// * `loop_end` - The end (exclusive) of the loop region
+/// * `crossfade` - The number of samples to crossfade at the loop seam
+/// * `length` - The number of samples to produce
+pub fn loop_with_crossfade(samples: &[f32], loop_start: usize, loop_end: usize, crossfade: usize, length: usize) -> Vec<f32> {
+    let loop_end = loop_end.min(samples.len());
+    if loop_start >= loop_end {
+        let mut out: Vec<f32> = samples.iter().copied().take(length).collect();
+        out.resize(length, 0.0);
+        return out;
+    }
+    // the crossfade can neither be longer than the loop, nor reach before the start of the sample
+    let crossfade = crossfade.min(loop_start).min(loop_end - loop_start);
+    let mut looped = samples[loop_start..loop_end].to_vec();
+    let fade_start = looped.len() - crossfade;
+    for i in 0..crossfade {
+        let t = (i + 1) as f32 / crossfade as f32;
+        looped[fade_start + i] = looped[fade_start + i] * (1.0 - t) + samples[loop_start - crossfade + i] * t;
+    }
+    let mut out: Vec<f32> = samples[..loop_start].iter().copied().take(length).collect();
+    while out.len() < length {
+        let remaining = length - out.len();
+        out.extend(looped.iter().take(remaining));
+    }
+    out
+}
+
+/// Sustains a sample to `length` samples, looping its stable region with a 50 ms crossfade.
+pub fn sustain(samples: &[f32], sample_rate: u32, length: usize) -> Vec<f32> {
+    sustain_loop(samples, sample_rate, find_loop_region(samples), length)
+}
+
+/// Sustains a sample to `length` samples, looping the given region (start inclusive, end exclusive) with a 50 ms crossfade.
+pub fn sustain_loop(samples: &[f32], sample_rate: u32, loop_region: (usize, usize), length: usize) -> Vec<f32> {
+    let (loop_start, loop_end) = loop_region;
+    let crossfade = sample_rate as usize / 20;
+    loop_with_crossfade(samples, loop_start, loop_end, crossfade, length)
+}
+
+#[cfg(test)]
+mod velocity_gain_tests {
+    use super::*;
+
+    #[test]
+    fn test_curve() {
+        assert_eq!(velocity_gain(0.0), 0.0);
+        assert_eq!(velocity_gain(0.5), 0.25);
+        assert_eq!(velocity_gain(1.0), 1.0);
+        assert_eq!(velocity_gain(1.5), 1.0);
+    }
+
+    #[test]
+    fn test_lower_velocity_lowers_peak() {
+        let samples: Vec<f32> = (0..100).map(|i| (i as f32 / 5.0).sin() * 0.8).collect();
+        let mut loud = samples.clone();
+        apply_gain(&mut loud, velocity_gain(1.0));
+        let mut soft = samples.clone();
+        apply_gain(&mut soft, velocity_gain(0.5));
+        assert!(peak_amplitude(&soft) < peak_amplitude(&loud));
+        assert!((peak_amplitude(&soft) / peak_amplitude(&loud) - 0.25).abs() < 1e-6);
+    }
+}
+
+#[cfg(test)]
+mod clipping_tests {
+    use super::*;
+
+    #[test]
+    fn test_in_range() {
+        let samples = [0.0, 0.5, -1.0, 1.0, -0.25];
+        assert_eq!(peak_amplitude(&samples), 1.0);
+        assert_eq!(count_clipped(&samples), 0);
+        assert_eq!(peak_amplitude(&[]), 0.0);
+        assert_eq!(count_clipped(&[]), 0);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let samples = [0.5, 1.5, -0.75, -2.0, 1.0001];
+        assert_eq!(peak_amplitude(&samples), 2.0);
+        assert_eq!(count_clipped(&samples), 3);
+    }
+}
+
+#[cfg(test)]
+mod trim_with_fade_tests {
+    use super::*;
+
+    #[test]
+    fn test_cut() {
+        let samples = vec![1.0; 100];
+        let out = trim_with_fade(&samples, 50, 10, FadeCurve::Linear);
+        assert_eq!(out.len(), 50);
+        assert_eq!(out[..40], samples[..40]);
+        assert!(out[40..].windows(2).all(|w| w[1] < w[0]));
+        assert_eq!(out[49], 0.0);
+    }
+
+    #[test]
+    fn test_pad() {
+        let samples = vec![1.0; 10];
+        let out = trim_with_fade(&samples, 20, 5, FadeCurve::Linear);
+        assert_eq!(out[..10], samples[..]);
+        assert_eq!(out[10..], [0.0; 10]);
+    }
+}
+
+#[cfg(test)]
+mod fade_curve_tests {
+    use super::*;
+
+    #[test]
+    fn test_end_points() {
+        for curve in [FadeCurve::Linear, FadeCurve::Exponential, FadeCurve::Logarithmic] {
+            assert!(curve.gain(0.0).abs() < 1e-6);
+            assert!((curve.gain(1.0) - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_exponential_decays_faster_initially() {
+        let samples = vec![1.0; 100];
+        let linear = trim_with_fade(&samples, 50, 20, FadeCurve::Linear);
+        let exponential = trim_with_fade(&samples, 50, 20, FadeCurve::Exponential);
+        let logarithmic = trim_with_fade(&samples, 50, 20, FadeCurve::Logarithmic);
+        assert_eq!(exponential[..30], linear[..30]);
+        for i in 30..49 {
+            assert!(exponential[i] < linear[i]);
+            assert!(logarithmic[i] > linear[i]);
+        }
+        assert_eq!(exponential[49], 0.0);
+    }
+
+    #[test]
+    fn test_envelope() {
+        let mut samples = vec![1.0; 10];
+        apply_envelope(&mut samples, 4, 4, FadeCurve::Linear);
+        assert_eq!(samples, vec![0.0, 0.25, 0.5, 0.75, 1.0, 1.0, 0.75, 0.5, 0.25, 0.0]);
+        let mut samples = vec![1.0; 10];
+        apply_envelope(&mut samples, 4, 0, FadeCurve::Exponential);
+        assert!(samples[1] < 0.25 && samples[3] < 0.75);
+        assert_eq!(samples[4..], [1.0; 6]);
+        apply_envelope(&mut [], 4, 4, FadeCurve::Linear);
+    }
+}
+
+#[cfg(test)]
+mod white_noise_tests {
+    use super::*;
+
+    #[test]
+    fn test_white_noise() {
+        let noise = white_noise(1000, 7);
+        assert_eq!(noise.len(), 1000);
+        assert!(noise.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+        assert!(peak_amplitude(&noise) > 0.9);
+        assert_eq!(noise, white_noise(1000, 7));
+        assert_ne!(noise, white_noise(1000, 8));
+    }
+}
+
+#[cfg(test)]
+mod frame_samples_tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_division() {
+        let samples: Vec<f32> = (0..6).map(|i| i as f32).collect();
+        let frames = frame_samples(&samples, 3, PartialFrame::Pad);
+        assert_eq!(frames, vec![vec![0.0, 1.0, 2.0], vec![3.0, 4.0, 5.0]]);
+        assert_eq!(frame_samples(&samples, 3, PartialFrame::Truncate), frames);
+    }
+
+    #[test]
+    fn test_partial_frame() {
+        let samples: Vec<f32> = (0..5).map(|i| i as f32).collect();
+        assert_eq!(frame_samples(&samples, 3, PartialFrame::Pad), vec![vec![0.0, 1.0, 2.0], vec![3.0, 4.0, 0.0]]);
+        assert_eq!(frame_samples(&samples, 3, PartialFrame::Truncate), vec![vec![0.0, 1.0, 2.0]]);
+        assert!(frame_samples(&samples, 0, PartialFrame::Pad).is_empty());
+        assert!(frame_samples(&[], 3, PartialFrame::Pad).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod resample_linear_tests {
+    use super::*;
+
+    #[test]
+    fn test_length_keeps_duration() {
+        let samples = vec![0.5; 100];
+        assert_eq!(resample_linear(&samples, 500, 1000).len(), 200);
+        assert_eq!(resample_linear(&samples, 1000, 500).len(), 50);
+        assert_eq!(resample_linear(&samples, 1000, 1000), samples);
+        assert!(resample_linear(&[], 500, 1000).is_empty());
+    }
+
+    #[test]
+    fn test_interpolates() {
+        assert_eq!(resample_linear(&[0.0, 1.0, 0.0, -1.0], 1, 2), vec![0.0, 0.5, 1.0, 0.5, 0.0, -0.5, -1.0, -1.0]);
+    }
+}
+
+#[cfg(test)]
+mod loop_with_crossfade_tests {
+    use super::*;
+
+    fn ramp(len: usize) -> Vec<f32> {
+        (0..len).map(|i| i as f32 / len as f32).collect()
+    }
+
+    #[test]
+    fn test_length() {
+        let samples = ramp(1000);
+        assert_eq!(loop_with_crossfade(&samples, 250, 750, 100, 5000).len(), 5000);
+        assert_eq!(loop_with_crossfade(&samples, 250, 750, 100, 100).len(), 100);
+        assert_eq!(loop_with_crossfade(&[], 0, 0, 100, 10), vec![0.0; 10]);
+    }
+
+    #[test]
+    fn test_seam_is_crossfaded() {
+        let samples = ramp(1000);
+        let out = loop_with_crossfade(&samples, 250, 750, 100, 5000);
+        // without the crossfade the ramp would jump back by 0.5 at every seam
+        let max_jump = out.windows(2).map(|w| (w[1] - w[0]).abs()).fold(0.0, f32::max);
+        assert!(max_jump < 0.01);
+        // the first pass plays the sample unchanged up to the crossfade
+        assert_eq!(out[..650], samples[..650]);
+    }
+}
+
+#[cfg(test)]
+mod sustain_tests {
+    use super::*;
+
+    #[test]
+    fn test_length_matches_duration() {
+        let samples: Vec<f32> = (0..500).map(|i| (i as f32 / 10.0).sin()).collect();
+        let length = duration_to_samples(Duration::from_secs(2), 1000);
+        assert_eq!(length, 2000);
+        assert_eq!(sustain(&samples, 1000, length).len(), 2000);
+    }
+}
@@ -0,0 +1,74 @@
+use std::error::Error;
+use std::path::Path;
+use hound::{SampleFormat, WavSpec, WavWriter};
+
+/// Writes mono samples to a 32-bit float WAV file.
+///
+/// # Arguments
+/// * `samples` - The samples to write
+/// * `sample_rate` - The sample rate of the samples
+/// * `out` - The path of the WAV file
+pub fn write_wav(samples: &[f32], sample_rate: u32, out: &Path) -> Result<(), Box<dyn Error>> {
+    write_wav_with_channels(samples, 1, sample_rate, out)
+}
+
+/// Writes interleaved stereo samples (left, right, left, right, ...) to a 32-bit float WAV file.
+///
+/// # Arguments
+/// * `samples_interleaved` - The interleaved samples to write, must have an even length
+/// * `sample_rate` - The sample rate of the samples
+/// * `out` - The path of the WAV file
+pub fn write_stereo_wav(samples_interleaved: &[f32], sample_rate: u32, out: &Path) -> Result<(), Box<dyn Error>> {
+    if !samples_interleaved.len().is_multiple_of(2) {
+        return Err("Interleaved stereo samples must have an even length".into());
+    }
+    write_wav_with_channels(samples_interleaved, 2, sample_rate, out)
+}
+
+fn write_wav_with_channels(samples: &[f32], channels: u16, sample_rate: u32, out: &Path) -> Result<(), Box<dyn Error>> {
+    let spec = WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(out, spec)?;
+    for sample in samples {
+        writer.write_sample(*sample)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod write_wav_tests {
+    use std::env::temp_dir;
+    use hound::WavReader;
+    use super::*;
+
+    #[test]
+    fn test_mono() {
+        let out = temp_dir().join("ecotonova_write_wav_mono.wav");
+        write_wav(&[0.0, 0.5, -0.5], 44_100, &out).unwrap();
+        let reader = WavReader::open(&out).unwrap();
+        assert_eq!(reader.spec().channels, 1);
+        assert_eq!(reader.spec().sample_rate, 44_100);
+        assert_eq!(reader.len(), 3);
+    }
+
+    #[test]
+    fn test_stereo() {
+        let out = temp_dir().join("ecotonova_write_wav_stereo.wav");
+        write_stereo_wav(&[0.0, 0.1, 0.5, 0.6, -0.5, -0.4], 44_100, &out).unwrap();
+        let mut reader = WavReader::open(&out).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        let samples: Vec<f32> = reader.samples::<f32>().map(|s| s.unwrap()).collect();
+        assert_eq!(samples, vec![0.0, 0.1, 0.5, 0.6, -0.5, -0.4]);
+    }
+
+    #[test]
+    fn test_stereo_odd_length() {
+        let out = temp_dir().join("ecotonova_write_wav_odd.wav");
+        assert!(write_stereo_wav(&[0.0, 0.1, 0.5], 44_100, &out).is_err());
+    }
+}
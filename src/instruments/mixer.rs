@@ -0,0 +1,71 @@
+use crate::instruments::dsp::resample_linear;
+
+/// Mixes mono samples onto a common timeline at a fixed sample rate.
+///
+/// Samples at another sample rate are resampled to the rate of the mixer before they are added,
+/// and overlapping samples are summed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mixer {
+    sample_rate: u32,
+    samples: Vec<f32>,
+}
+
+impl Mixer {
+    pub fn new(sample_rate: u32) -> Self {
+        Self { sample_rate, samples: Vec::new() }
+    }
+
+    /// The sample rate of the mixed samples.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Adds samples to the timeline, extending it with silence if they end after it.
+    ///
+    /// # Arguments
+    /// * `offset` - The position on the timeline, in samples at the rate of the mixer, where the samples start
+    /// * `sample_rate` - The sample rate of the samples
+    /// * `samples` - The samples to add
+    pub fn add(&mut self, offset: usize, sample_rate: u32, samples: &[f32]) {
+        let resampled = resample_linear(samples, sample_rate, self.sample_rate);
+        let end = offset + resampled.len();
+        if self.samples.len() < end {
+            self.samples.resize(end, 0.0);
+        }
+        for (mixed, sample) in self.samples[offset..end].iter_mut().zip(resampled) {
+            *mixed += sample;
+        }
+    }
+
+    /// Finishes mixing.
+    ///
+    /// # Returns
+    /// * A tuple of
+    /// * 1. u32: The sample rate of the mix
+    /// * 2. Vec<f32>: The mixed samples
+    pub fn finish(self) -> (u32, Vec<f32>) {
+        (self.sample_rate, self.samples)
+    }
+}
+
+#[cfg(test)]
+mod mixer_tests {
+    use super::*;
+
+    #[test]
+    fn test_overlap_sums() {
+        let mut mixer = Mixer::new(1000);
+        mixer.add(0, 1000, &[0.25; 4]);
+        mixer.add(2, 1000, &[0.5; 4]);
+        let (sample_rate, samples) = mixer.finish();
+        assert_eq!(sample_rate, 1000);
+        assert_eq!(samples, vec![0.25, 0.25, 0.75, 0.75, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_resamples_to_mixer_rate() {
+        let mut mixer = Mixer::new(1000);
+        mixer.add(0, 500, &[1.0; 50]);
+        assert_eq!(mixer.finish().1.len(), 100);
+    }
+}
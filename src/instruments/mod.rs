@@ -1 +1,5 @@
-mod player;
\ No newline at end of file
+mod cache;
+mod dsp;
+mod export;
+mod mixer;
+pub(crate) mod player;
@@ -1,49 +1,345 @@
 use std::error::Error;
 use std::fmt::Display;
 use std::fs;
-use std::path::PathBuf;
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
 use pitch_shift::PitchShifter;
 use rodio::{OutputStream, Sink, Source};
 use rodio::buffer::SamplesBuffer;
 use stringcase::snake_case;
+use crate::instruments::cache::SampleCache;
+use crate::instruments::dsp::{apply_gain, duration_to_samples, find_loop_region, resample_linear, sustain, sustain_loop, trim_with_fade, velocity_gain, white_noise, Envelope, FadeCurve};
+use crate::instruments::mixer::Mixer;
 use crate::theory::interval::Interval;
+use crate::theory::melody::{ambitus, Melody, Note};
+use crate::theory::scale::Scale;
 use crate::theory::pitch::Pitch;
+use crate::theory::pitch_class::PitchClass;
 
+/// The environment variable that overrides the default samples directory.
+pub const SAMPLES_DIR_ENV: &str = "ECOTONOVA_SAMPLES_DIR";
+/// The samples directory used when no other directory is configured.
+pub const DEFAULT_SAMPLES_DIR: &str = "./resources/samples";
+
+static SAMPLES_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// The sample rate used when there is no sample to take it from, e.g. for a sequence of rests.
+pub const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+
+/// The extension of the sidecar file holding the loop points of a sample, e.g. `C4.loop` next to `C4.flac`.
+pub const LOOP_SIDECAR_EXTENSION: &str = "loop";
+
+/// The lowest frequency in hertz that is considered audible.
+pub const MIN_AUDIBLE_HERTZ: f32 = 20.0;
+/// The highest frequency in hertz that is considered audible.
+pub const MAX_AUDIBLE_HERTZ: f32 = 20_000.0;
+
+/// What the player does when a requested pitch falls outside the audible range.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum InaudiblePolicy {
+    #[default]
+    Play,
+    Warn,
+    Skip,
+}
+
+/// What `Instrument::play_with_policy` did with a pitch, so the caller can report an inaudible one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayOutcome {
+    /// The pitch was played
+    Played,
+    /// The pitch was played under `InaudiblePolicy::Warn` although its frequency in hertz is outside the audible range
+    PlayedInaudible(f32),
+    /// The pitch was not played under `InaudiblePolicy::Skip`, as its frequency in hertz is outside the audible range
+    Skipped(f32),
+}
+
+/// Options for rendering a melody.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// The fade-out applied where a sample is cut off at the end of its note
+    pub fade_out: Duration,
+    /// The shape of the fade-out
+    pub fade_curve: FadeCurve,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            fade_out: Duration::from_millis(10),
+            fade_curve: FadeCurve::Linear,
+        }
+    }
+}
+
+/// A decoded sample file together with the region to loop when sustaining it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedSample {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+    /// The start (inclusive) and the end (exclusive) of the loop region
+    pub loop_region: (usize, usize),
+}
+
+/// Rendered samples together with their sample rate and playback duration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderResult {
+    pub rate: u32,
+    pub samples: Vec<f32>,
+    /// How long the samples take to play at the sample rate
+    pub duration: Duration,
+}
+
+impl RenderResult {
+    pub fn new(rate: u32, samples: Vec<f32>) -> Self {
+        let duration = Duration::from_secs_f64(samples.len() as f64 / rate as f64);
+        Self { rate, samples, duration }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum Instrument {
     SalamanderGrandPiano,
+    /// An instrument read from a folder of FLAC samples named after their pitches, e.g. `C#4.flac`
+    Custom(PathBuf),
 }
 
 impl Display for Instrument {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Instrument::SalamanderGrandPiano => write!(f, "SalamanderGrandPiano"),
+            Instrument::Custom(path) => write!(f, "{}", path.file_name().unwrap_or(path.as_os_str()).to_string_lossy()),
         }
     }
 }
 
 impl Instrument {
+    /// Gets the sample folder of the instrument within the configured samples directory, see `samples_dir`.
     pub fn sample_folder_path(&self) -> PathBuf {
+        self.sample_folder_path_in(&samples_dir())
+    }
+    /// Gets the sample folder of the instrument within the given samples directory.
+    ///
+    /// A custom instrument always uses its own folder.
+    pub fn sample_folder_path_in(&self, samples_dir: &Path) -> PathBuf {
         match self {
             Instrument::SalamanderGrandPiano => {
                 let folder_name = snake_case(&self.to_string());
-                let folder_path = PathBuf::from(&format!("./resources/samples/{}", folder_name));
-                folder_path
+                samples_dir.join(folder_name)
             }
+            Instrument::Custom(path) => path.clone(),
+        }
+    }
+    /// Gets the pitches that have a sample file in the sample folder, in no particular order.
+    pub fn available_pitches(&self) -> Result<Vec<Pitch>, Box<dyn Error>> {
+        let sample_folder_path = self.sample_folder_path();
+        if !sample_folder_path.exists() {
+            return Err("Sample folder not found".into());
+        }
+        let pitches = fs::read_dir(&sample_folder_path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("flac"))
+            .filter_map(|path| path.file_stem().map(|file_name| file_name.to_string_lossy().to_string()))
+            .filter_map(|file_name| Pitch::try_from(file_name).ok())
+            .collect();
+        Ok(pitches)
+    }
+    /// Lists the pitches of a scale that have no sample file of their own, so they would be pitch-shifted from a neighbour.
+    ///
+    /// Enharmonic spellings count as the same pitch, e.g. the sample `C#4.flac` covers Db4.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The scale to check
+    /// * `root` - The root of the scale in the lowest octave to check
+    /// * `octaves` - The number of octaves to check, ascending from the root
+    pub fn missing_samples_for(&self, scale: &Scale, root: Pitch, octaves: u8) -> Result<Vec<Pitch>, Box<dyn Error>> {
+        let sampled = self.available_pitches()?;
+        let degrees = scale.degree_offsets().len() * octaves as usize;
+        let missing = (1..=degrees)
+            .filter_map(|degree| scale.degree(&root, degree).ok())
+            .filter(|pitch| !sampled.contains(pitch))
+            .collect();
+        Ok(missing)
+    }
+    /// Gets the lowest and the highest sampled pitch of the instrument.
+    pub fn range(&self) -> Result<(Pitch, Pitch), Box<dyn Error>> {
+        let pitches = self.available_pitches()?;
+        let lowest = pitches.iter().min().ok_or("No samples found in the sample folder")?;
+        let highest = pitches.iter().max().ok_or("No samples found in the sample folder")?;
+        Ok((lowest.clone(), highest.clone()))
+    }
+    /// Gets the sample rate the samples of the instrument were recorded at, e.g. to set up the rate of a `Mixer`.
+    ///
+    /// Only the stream info header of one sample file is read, the samples themselves are not decoded.
+    /// The samples of an instrument are expected to share one sample rate.
+    pub fn native_sample_rate(&self) -> Result<u32, Box<dyn Error>> {
+        let pitch = self.available_pitches()?.into_iter().next().ok_or("No samples found in the sample folder")?;
+        let pitch_file_path = self.sample_folder_path().join(format!("{}", pitch)).with_extension("flac");
+        let reader = claxon::FlacReader::open(pitch_file_path)?;
+        Ok(reader.streaminfo().sample_rate)
+    }
+    /// Moves the pitch by whole octaves until it lies within the sampled range of the instrument,
+    /// so it can be played without shifting a sample far from its recorded pitch.
+    ///
+    /// A pitch within the range is returned unchanged, and the spelling of the pitch is kept.
+    ///
+    /// # Returns
+    ///
+    /// The pitch in the nearest octave within the range, or an error if no octave of the pitch lies within the range.
+    pub fn fold_into_range(&self, pitch: Pitch) -> Result<Pitch, Box<dyn Error>> {
+        let (lowest, highest) = self.range()?;
+        let mut folded = pitch;
+        while folded < lowest {
+            folded.octave = folded.octave.checked_add(1).ok_or("Pitch cannot be folded into the range")?;
         }
+        while folded > highest {
+            folded.octave = folded.octave.checked_sub(1).ok_or("Pitch cannot be folded into the range")?;
+        }
+        if folded < lowest {
+            return Err(format!("No octave of {} lies within {}..{}", folded, lowest, highest).into());
+        }
+        Ok(folded)
+    }
+    /// Places a pitch class in the octave where the instrument sounds best: the sampled pitch of that class
+    /// nearest the center of the sampled range or, if no pitch of that class is sampled, the octave nearest the center.
+    pub fn best_register(&self, pitch_class: PitchClass) -> Result<Pitch, Box<dyn Error>> {
+        let (lowest, highest) = self.range()?;
+        let center = (f32::from(lowest) + f32::from(highest)) / 2.0;
+        let distance_to_center = |pitch: &Pitch| (f32::from(pitch.clone()) - center).abs();
+        let sampled: Vec<Pitch> = self.available_pitches()?
+            .into_iter()
+            .filter(|pitch| PitchClass::from(pitch.clone()) == pitch_class)
+            .collect();
+        let candidates = if sampled.is_empty() {
+            // the nearest placement lies in the octave of the center or in one of its neighbours
+            let octave = (center / 6.0).floor() as i8;
+            (octave.saturating_sub(1)..=octave.saturating_add(1))
+                .map(|octave| Pitch::new(pitch_class.name.clone(), octave, pitch_class.accidental.clone()))
+                .collect()
+        } else {
+            sampled
+        };
+        let best = candidates.into_iter()
+            .min_by(|a, b| distance_to_center(a).total_cmp(&distance_to_center(b)))
+            .ok_or("No register found")?;
+        Ok(best)
+    }
+    /// Transposes the whole melody by whole octaves so that it overlaps the sampled range of the instrument as well as possible,
+    /// keeping the pitch shifts of the samples small.
+    ///
+    /// The melody is moved by the fewest octaves that leave the least of it outside the range,
+    /// so a melody that already lies within the range is returned unchanged.
+    pub fn fit_melody(&self, melody: &Melody) -> Result<Melody, Box<dyn Error>> {
+        let (lowest, highest) = self.range()?;
+        let pitches: Vec<Pitch> = melody.notes.iter().filter_map(|note| note.pitch.clone()).collect();
+        let interval = match ambitus(&pitches) {
+            Some(interval) => interval,
+            None => return Ok(melody.clone()),
+        };
+        let (lowest, highest) = (f32::from(lowest), f32::from(highest));
+        let (low, high) = (f32::from(interval.lower().clone()), f32::from(interval.upper().clone()));
+        // how far the melody reaches outside the range when moved by a number of octaves
+        let outside = |octaves: i32| {
+            let shift = octaves as f32 * 6.0;
+            (lowest - (low + shift)).max(0.0) + ((high + shift) - highest).max(0.0)
+        };
+        let octaves = (-10..=10)
+            .min_by(|a: &i32, b: &i32| outside(*a).total_cmp(&outside(*b)).then(a.abs().cmp(&b.abs())))
+            .unwrap_or(0);
+        Ok(melody.transpose(octaves * 12))
     }
     pub fn play(&self, pitch: Pitch) -> Result<(), Box<dyn Error>> {
         let (sample_rate, samples) = generate_pitch_samples(self.clone(), pitch)?;
-        let (_stream, stream_handle) = OutputStream::try_default()?;
-        let source = SamplesBuffer::new(1, sample_rate, samples).convert_samples::<f32>();
-        let sink = Sink::try_new(&stream_handle)?;
-        sink.append(source);
-        sink.sleep_until_end();
-        Ok(())
+        play_samples(sample_rate, samples)
+    }
+    /// Plays only the beginning of the pitch, see `render_preview`.
+    pub fn play_preview(&self, pitch: Pitch, preview: Duration) -> Result<(), Box<dyn Error>> {
+        let (sample_rate, samples) = render_preview(self.clone(), pitch, preview)?;
+        play_samples(sample_rate, samples)
+    }
+    /// Plays the pitch for exactly the given duration, see `render_for`.
+    pub fn play_for(&self, pitch: Pitch, duration: Duration) -> Result<(), Box<dyn Error>> {
+        let (sample_rate, samples) = render_for(self.clone(), pitch, duration)?;
+        play_samples(sample_rate, samples)
+    }
+    /// Plays the notes one after another, with silence for the rests.
+    pub fn play_sequence(&self, notes: &[Note]) -> Result<(), Box<dyn Error>> {
+        let (sample_rate, samples) = render_sequence(self.clone(), notes)?;
+        play_samples(sample_rate, samples)
+    }
+    /// Plays the pitch, checking first whether its frequency is audible.
+    ///
+    /// With `InaudiblePolicy::Warn` an inaudible pitch is played and reported in the outcome,
+    /// with `InaudiblePolicy::Skip` an inaudible pitch is not rendered at all.
+    pub fn play_with_policy(&self, pitch: Pitch, policy: InaudiblePolicy) -> Result<PlayOutcome, Box<dyn Error>> {
+        let hertz = pitch.to_hertz();
+        let outcome = if is_audible(hertz) {
+            PlayOutcome::Played
+        } else {
+            match policy {
+                InaudiblePolicy::Play => PlayOutcome::Played,
+                InaudiblePolicy::Warn => PlayOutcome::PlayedInaudible(hertz),
+                InaudiblePolicy::Skip => return Ok(PlayOutcome::Skipped(hertz)),
+            }
+        };
+        self.play(pitch)?;
+        Ok(outcome)
+    }
+}
+
+/// Sets the directory the sample folders of the instruments are read from, or resets it with `None`.
+pub fn set_samples_dir(dir: Option<PathBuf>) {
+    *SAMPLES_DIR.write().unwrap() = dir;
+}
+
+/// Gets the directory the sample folders of the instruments are read from.
+///
+/// In order of precedence, this is the directory set with `set_samples_dir`,
+/// the directory in the `ECOTONOVA_SAMPLES_DIR` environment variable, or `DEFAULT_SAMPLES_DIR`.
+pub fn samples_dir() -> PathBuf {
+    resolve_samples_dir(SAMPLES_DIR.read().unwrap().as_deref(), env::var_os(SAMPLES_DIR_ENV))
+}
+
+/// Resolves the samples directory from the explicitly set directory and the value of the environment variable.
+///
+/// # Arguments
+/// * `explicit` - The directory set with `set_samples_dir`, if any
+/// * `env` - The value of the `ECOTONOVA_SAMPLES_DIR` environment variable, if set
+///
+/// # Returns
+/// * `explicit` if given, otherwise `env` if given, otherwise `DEFAULT_SAMPLES_DIR`
+fn resolve_samples_dir(explicit: Option<&Path>, env: Option<OsString>) -> PathBuf {
+    match (explicit, env) {
+        (Some(dir), _) => dir.to_path_buf(),
+        (None, Some(dir)) => PathBuf::from(dir),
+        (None, None) => PathBuf::from(DEFAULT_SAMPLES_DIR),
     }
 }
 
+fn play_samples(sample_rate: u32, samples: Vec<f32>) -> Result<(), Box<dyn Error>> {
+    let (_stream, stream_handle) = OutputStream::try_default()?;
+    let source = SamplesBuffer::new(1, sample_rate, samples).convert_samples::<f32>();
+    let sink = Sink::try_new(&stream_handle)?;
+    sink.append(source);
+    sink.sleep_until_end();
+    Ok(())
+}
+
+/// Clamps a frequency to the audible range, `MIN_AUDIBLE_HERTZ..=MAX_AUDIBLE_HERTZ`.
+pub fn clamp_audible(freq: f32) -> f32 {
+    freq.clamp(MIN_AUDIBLE_HERTZ, MAX_AUDIBLE_HERTZ)
+}
+
+/// Checks whether a frequency lies within the audible range.
+pub fn is_audible(freq: f32) -> bool {
+    clamp_audible(freq) == freq
+}
+
 /// Generate pitch samples for the given instrument and pitch.
 ///
 /// This function reads the sample file for the given instrument and pitch, and generates samples for the pitch.
@@ -61,55 +357,699 @@ impl Instrument {
 /// * 1. u32: The sample rate of the generated samples
 /// * 2. Vec<f32>: A vector of samples for the given instrument and pitch
 pub fn generate_pitch_samples(instrument: Instrument, pitch: Pitch) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    let (pitch_file_path, shift_steps) = find_sample_file(&instrument, &pitch)?;
+    let (sample_rate, samples) = decode_sample_file(&pitch_file_path)?;
+    Ok((sample_rate, shift_samples(&samples, sample_rate, shift_steps)))
+}
+
+/// Generates the samples of a pitch like `generate_pitch_samples`, also reporting how long they take to play.
+pub fn render_pitch(instrument: Instrument, pitch: Pitch) -> Result<RenderResult, Box<dyn Error>> {
+    let (sample_rate, samples) = generate_pitch_samples(instrument, pitch)?;
+    Ok(RenderResult::new(sample_rate, samples))
+}
+
+/// Finds the sample file to generate the given pitch from.
+///
+/// # Returns
+/// * A tuple of
+/// * 1. PathBuf: The sample file of the pitch, or of the nearest available pitch if there is none
+/// * 2. f32: The number of semitones to shift the samples of the file by to get the pitch, positive to shift up
+pub fn find_sample_file(instrument: &Instrument, pitch: &Pitch) -> Result<(PathBuf, f32), Box<dyn Error>> {
     // get the sample folder path
     let sample_folder_path = instrument.sample_folder_path();
     if !sample_folder_path.exists() {
         return Err("Sample folder not found".into());
     }
     // get the pitch file path
-    let mut shift_steps: f32 = 0.0;  // the resample pitch shift
-    let pitch_file_name = format!("{}", pitch);
-    let mut pitch_file_path = sample_folder_path.join(pitch_file_name).with_extension("flac");
-    if !pitch_file_path.exists() {
-        // get all the audio files in the sample folder
-        let audio_file_names: Vec<String> = fs::read_dir(&sample_folder_path)?
-            .filter_map(|entry| entry.ok())
-            .map(|entry| entry.path())
-            .filter(|path| path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("flac"))
-            .filter_map(|path| path.file_stem().map(|file_name| file_name.to_string_lossy().to_string()))
-            .collect();
-        // find the nearest pitch
-        let pitches: Vec<Pitch> = audio_file_names.iter().map(|file_name| Pitch::try_from(file_name.to_string()).unwrap()).collect();
-        let new_pitch = pitch.get_the_nearest_pitch(pitches);
-        // set the pitch file path
-        pitch_file_path = sample_folder_path.join(format!("{}", new_pitch)).with_extension("flac");
-        // set the shift steps
-        shift_steps = Interval::new(pitch.clone(), new_pitch.clone()).get_number_of_semitones(false) as f32;
-        if new_pitch < pitch {
-            shift_steps = -shift_steps;
-        }
+    let pitch_file_path = sample_folder_path.join(format!("{}", pitch)).with_extension("flac");
+    if pitch_file_path.exists() {
+        return Ok((pitch_file_path, 0.0));
+    }
+    // find the nearest pitch among the audio files in the sample folder
+    let pitches = instrument.available_pitches()?;
+    if pitches.is_empty() {
+        return Err("No samples found in the sample folder".into());
     }
-    // read the sample
-    let mut reader = claxon::FlacReader::open(pitch_file_path).unwrap();
+    let new_pitch = pitch.get_the_nearest_pitch(pitches);
+    // get the shift steps
+    let mut shift_steps = Interval::new(pitch.clone(), new_pitch.clone()).get_number_of_semitones(false) as f32;
+    if new_pitch > *pitch {
+        shift_steps = -shift_steps;
+    }
+    Ok((sample_folder_path.join(format!("{}", new_pitch)).with_extension("flac"), shift_steps))
+}
+
+/// Decodes a FLAC sample file into mono samples normalized to -1.0..1.0.
+///
+/// Only the first channel of a stereo file is kept.
+///
+/// # Returns
+/// * A tuple of
+/// * 1. u32: The sample rate of the file
+/// * 2. Vec<f32>: The decoded samples
+pub fn decode_sample_file(path: &Path) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    let mut reader = claxon::FlacReader::open(path)?;
     let meta_info = reader.streaminfo();
     if meta_info.channels > 2 {
         return Err("Only mono and stereo files are supported".into());
     }
     let bit = 2f32.powf(meta_info.bits_per_sample as f32) / 2.0 - 1.0; // calculate the bit for normalization
-    let mut samples: Vec<f32> = reader.samples().map(|s| s.unwrap() as f32 / bit).collect(); // read the samples and normalize
+    let mut samples: Vec<f32> = reader.samples().map(|s| s.map(|s| s as f32 / bit)).collect::<Result<_, _>>()?; // read the samples and normalize
     if meta_info.channels == 2 {
         samples = samples.iter().enumerate().filter(|(i, _)| i % 2 == 0).map(|(_, s)| *s).collect(); // get only one channel
     }
-    // pitch shift
-    let mut out_samples = samples.clone();
-    let mut ps = PitchShifter::new(50, meta_info.sample_rate as usize);
+    Ok((meta_info.sample_rate, samples))
+}
+
+/// Reads the loop points of a sample file from its sidecar file, see `LOOP_SIDECAR_EXTENSION`.
+///
+/// The sidecar holds the start (inclusive) and the end (exclusive) sample index of the loop region,
+/// separated by whitespace, e.g. `22050 66150`.
+///
+/// # Returns
+///
+/// The loop region, `None` if the sample has no sidecar file, or an error if the sidecar cannot be read or parsed.
+pub fn read_loop_points(sample_path: &Path) -> Result<Option<(usize, usize)>, Box<dyn Error>> {
+    let sidecar_path = sample_path.with_extension(LOOP_SIDECAR_EXTENSION);
+    if !sidecar_path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&sidecar_path)?;
+    let points: Vec<usize> = content.split_whitespace().map(|point| point.parse()).collect::<Result<_, _>>()?;
+    match points.as_slice() {
+        [start, end] => Ok(Some((*start, *end))),
+        _ => Err(format!("Expected a start and an end in {}", sidecar_path.display()).into()),
+    }
+}
+
+/// Decodes a sample file and attaches its loop region, read from its sidecar file or found by `find_loop_region`.
+///
+/// Fails if the loop region of the sidecar is empty or reaches beyond the end of the sample.
+pub fn load_sample(path: &Path) -> Result<LoadedSample, Box<dyn Error>> {
+    let (sample_rate, samples) = decode_sample_file(path)?;
+    let loop_region = match read_loop_points(path)? {
+        Some((start, end)) if start < end && end <= samples.len() => (start, end),
+        Some((start, end)) => return Err(format!("Invalid loop region {}..{} for {} samples", start, end, samples.len()).into()),
+        None => find_loop_region(&samples),
+    };
+    Ok(LoadedSample { sample_rate, samples, loop_region })
+}
+
+/// Shifts the pitch of the samples by a number of semitones, positive to shift up.
+///
+/// Without a shift the samples are returned untouched, bypassing the pitch shifter.
+pub fn shift_samples(samples: &[f32], sample_rate: u32, shift_steps: f32) -> Vec<f32> {
+    if shift_steps == 0.0 {
+        return samples.to_vec();
+    }
+    let mut out_samples = samples.to_vec();
+    let mut ps = PitchShifter::new(50, sample_rate as usize);
     ps.shift_pitch(
         5,
-        -shift_steps, // 3 semitones
-        &samples,
+        shift_steps,
+        samples,
         &mut out_samples,
     );
-    let samples = out_samples;
+    out_samples
+}
 
-    Ok((meta_info.sample_rate, samples))
-}
\ No newline at end of file
+/// Renders a note for exactly its duration as a melody of one note with the default render options, see `render_melody`.
+///
+/// The sample is cut with a fade-out at the end of the note, or padded with silence if it is shorter than the note,
+/// and its amplitude is scaled by the velocity of the note, see `velocity_gain`.
+/// A rest renders silence at `DEFAULT_SAMPLE_RATE`.
+///
+/// # Returns
+/// * A tuple of
+/// * 1. u32: The sample rate of the rendered note
+/// * 2. Vec<f32>: The samples of the rendered note
+pub fn render_note(instrument: Instrument, note: &Note) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    render_melody(instrument, &Melody::new(vec![note.clone()]), &RenderOptions::default())
+}
+
+/// Renders a rest as silence.
+pub fn render_rest(duration: Duration, sample_rate: u32) -> Vec<f32> {
+    vec![0.0; duration_to_samples(duration, sample_rate)]
+}
+
+/// Renders a hit of white noise shaped by the envelope, e.g. a basic snare or hi-hat without sample files.
+///
+/// The noise is the same on every call, see `white_noise`.
+pub fn render_noise_hit(duration: Duration, sample_rate: u32, env: &Envelope) -> Vec<f32> {
+    let mut samples = white_noise(duration_to_samples(duration, sample_rate), 1);
+    env.apply(&mut samples, sample_rate);
+    samples
+}
+
+/// Renders the notes one after another as a melody with the default render options, see `render_melody`.
+///
+/// # Returns
+/// * A tuple of
+/// * 1. u32: The sample rate of the rendered sequence
+/// * 2. Vec<f32>: The samples of the rendered sequence
+pub fn render_sequence(instrument: Instrument, notes: &[Note]) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    render_melody(instrument, &Melody::new(notes.to_vec()), &RenderOptions::default())
+}
+
+/// Renders a melody, decoding every sample file only once.
+///
+/// See `render_melody_with_cache`.
+pub fn render_melody(instrument: Instrument, melody: &Melody, options: &RenderOptions) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    render_melody_with_cache(instrument, melody, options, &mut SampleCache::new())
+}
+
+/// Renders a melody, taking the decoded sample files from a cache.
+///
+/// Every distinct sample file is decoded once, then shifted, trimmed with a fade-out and scaled by velocity for each note.
+/// The melody takes the sample rate of its first pitched note. Notes from sample files of another rate are resampled to it,
+/// see `resample_linear`, and rests are rendered as silence at that rate.
+///
+/// # Returns
+/// * A tuple of
+/// * 1. u32: The sample rate of the rendered melody
+/// * 2. Vec<f32>: The samples of the rendered melody
+pub fn render_melody_with_cache(instrument: Instrument, melody: &Melody, options: &RenderOptions, cache: &mut SampleCache) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    let mut sample_rate = None;
+    let mut rendered_notes = Vec::with_capacity(melody.notes.len());
+    for note in &melody.notes {
+        let pitch = match &note.pitch {
+            Some(pitch) => pitch,
+            None => {
+                rendered_notes.push(None);
+                continue;
+            }
+        };
+        let (pitch_file_path, shift_steps) = find_sample_file(&instrument, pitch)?;
+        let (note_sample_rate, samples) = cache.get(&pitch_file_path)?;
+        let melody_sample_rate = *sample_rate.get_or_insert(*note_sample_rate);
+        let shifted = shift_samples(samples, *note_sample_rate, shift_steps);
+        let resampled = resample_linear(&shifted, *note_sample_rate, melody_sample_rate);
+        let length = duration_to_samples(note.duration, melody_sample_rate);
+        let mut samples = trim_with_fade(&resampled, length, duration_to_samples(options.fade_out, melody_sample_rate), options.fade_curve.clone());
+        apply_gain(&mut samples, velocity_gain(note.velocity));
+        rendered_notes.push(Some(samples));
+    }
+    // rests are rendered last, once the sample rate of the melody is known
+    let sample_rate = sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE);
+    let samples = melody.notes.iter()
+        .zip(rendered_notes)
+        .flat_map(|(note, samples)| samples.unwrap_or_else(|| render_rest(note.duration, sample_rate)))
+        .collect();
+    Ok((sample_rate, samples))
+}
+
+/// Renders the first `preview` of the pitch, fading out at the cut so a sample can be auditioned
+/// without waiting for its full sustain.
+///
+/// A sample shorter than the preview is padded with silence.
+pub fn render_preview(instrument: Instrument, pitch: Pitch, preview: Duration) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    let (sample_rate, samples) = generate_pitch_samples(instrument, pitch)?;
+    let length = duration_to_samples(preview, sample_rate);
+    let options = RenderOptions::default();
+    let fade = duration_to_samples(options.fade_out, sample_rate);
+    Ok((sample_rate, trim_with_fade(&samples, length, fade, options.fade_curve)))
+}
+
+/// Renders the pitch for exactly the given duration.
+///
+/// A sample longer than the duration is cut with a fade-out, a shorter one is sustained by looping, see `sustain`.
+pub fn render_for(instrument: Instrument, pitch: Pitch, duration: Duration) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    let (sample_rate, samples) = generate_pitch_samples(instrument, pitch)?;
+    let length = duration_to_samples(duration, sample_rate);
+    if length > samples.len() {
+        return Ok((sample_rate, sustain(&samples, sample_rate, length)));
+    }
+    let options = RenderOptions::default();
+    let fade = duration_to_samples(options.fade_out, sample_rate);
+    Ok((sample_rate, trim_with_fade(&samples, length, fade, options.fade_curve)))
+}
+
+/// Renders several parts, each a melody played by its own instrument, and mixes them onto a common timeline.
+///
+/// Every part starts at the beginning of the timeline, e.g. a piano melody over sustained chords.
+/// The mix takes the sample rate of the first part, the other parts are resampled to it, see `Mixer`.
+/// Sample files shared between the parts are decoded once.
+///
+/// # Returns
+/// * A tuple of
+/// * 1. u32: The sample rate of the mix
+/// * 2. Vec<f32>: The mixed samples, as long as the longest part
+pub fn render_arrangement(parts: Vec<(Instrument, Melody)>, options: &RenderOptions) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    let mut cache = SampleCache::new();
+    let mut mixer: Option<Mixer> = None;
+    for (instrument, melody) in parts {
+        let (sample_rate, samples) = render_melody_with_cache(instrument, &melody, options, &mut cache)?;
+        mixer.get_or_insert_with(|| Mixer::new(sample_rate)).add(0, sample_rate, &samples);
+    }
+    Ok(mixer.unwrap_or_else(|| Mixer::new(DEFAULT_SAMPLE_RATE)).finish())
+}
+
+/// Renders a continuous drone of the pitch for the given instrument.
+///
+/// Unlike playing a sequence of notes, the sample is rendered once and its loop region, see `load_sample`, is looped
+/// with a crossfade, so the tone can be sustained seamlessly for an arbitrary duration.
+///
+/// # Returns
+/// * A tuple of
+/// * 1. u32: The sample rate of the drone
+/// * 2. Vec<f32>: The samples of the drone, exactly `duration` long
+pub fn render_drone(instrument: Instrument, pitch: Pitch, duration: Duration) -> Result<(u32, Vec<f32>), Box<dyn Error>> {
+    let (pitch_file_path, shift_steps) = find_sample_file(&instrument, &pitch)?;
+    let sample = load_sample(&pitch_file_path)?;
+    let samples = shift_samples(&sample.samples, sample.sample_rate, shift_steps);
+    let length = duration_to_samples(duration, sample.sample_rate);
+    Ok((sample.sample_rate, sustain_loop(&samples, sample.sample_rate, sample.loop_region, length)))
+}
+
+/// An instrument reading the test samples in a folder of `resources/test_samples`.
+#[cfg(test)]
+fn test_instrument(folder: &str) -> Instrument {
+    Instrument::Custom(Path::new("./resources/test_samples").join(folder))
+}
+
+#[cfg(test)]
+mod render_melody_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_decodes_each_sample_once() {
+        let instrument = test_instrument("piano");
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let melody = Melody::new(vec![
+            Note::new(c4.clone(), Duration::from_millis(250)),
+            Note::new(Pitch::new_without_accidental(PitchName::D, 4), Duration::from_millis(125)),
+            Note::rest(Duration::from_millis(125)),
+            Note::new(c4, Duration::from_millis(250)),
+        ]);
+        let mut cache = SampleCache::new();
+        let (sample_rate, samples) = render_melody_with_cache(instrument, &melody, &RenderOptions::default(), &mut cache).unwrap();
+        assert_eq!(cache.decodes(), 1);
+        assert_eq!(sample_rate, 8000);
+        assert_eq!(samples.len(), duration_to_samples(Duration::from_millis(750), 8000));
+    }
+
+    #[test]
+    fn test_missing_folder() {
+        let instrument = test_instrument("missing");
+        let melody = Melody::new(vec![Note::new(Pitch::new_without_accidental(PitchName::C, 4), Duration::from_millis(250))]);
+        assert!(render_melody(instrument, &melody, &RenderOptions::default()).is_err());
+    }
+}
+
+#[cfg(test)]
+mod render_arrangement_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_parts_are_summed() {
+        let instrument = test_instrument("piano");
+        let melody = Melody::new(vec![Note::new(Pitch::new_without_accidental(PitchName::C, 4), Duration::from_millis(100))]);
+        let chords = Melody::new(vec![Note::new(Pitch::new_without_accidental(PitchName::G, 4), Duration::from_millis(200))]);
+        let options = RenderOptions::default();
+        let (_, melody_samples) = render_melody(instrument.clone(), &melody, &options).unwrap();
+        let (_, chord_samples) = render_melody(instrument.clone(), &chords, &options).unwrap();
+        let (sample_rate, mixed) = render_arrangement(vec![(instrument.clone(), melody), (instrument, chords)], &options).unwrap();
+        assert_eq!(sample_rate, 8000);
+        assert_eq!(mixed.len(), chord_samples.len());
+        assert_eq!(mixed.len(), duration_to_samples(Duration::from_millis(200), 8000));
+        // the melody overlaps the start of the chords, after it only the chords sound
+        assert!(mixed.iter().zip(melody_samples.iter().chain(std::iter::repeat(&0.0))).zip(&chord_samples)
+            .all(|((mixed, melody), chord)| (mixed - (melody + chord)).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_no_parts() {
+        assert_eq!(render_arrangement(Vec::new(), &RenderOptions::default()).unwrap(), (DEFAULT_SAMPLE_RATE, Vec::new()));
+    }
+}
+
+#[cfg(test)]
+mod native_sample_rate_tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_rate() {
+        let instrument = test_instrument("piano");
+        assert_eq!(instrument.native_sample_rate().unwrap(), 8000);
+    }
+
+    #[test]
+    fn test_missing_folder() {
+        let instrument = test_instrument("missing");
+        assert!(instrument.native_sample_rate().is_err());
+    }
+}
+
+#[cfg(test)]
+mod missing_samples_for_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_missing_samples() {
+        let instrument = test_instrument("piano");
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let missing: Vec<String> = instrument.missing_samples_for(&Scale::major(), c4.clone(), 1).unwrap().iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(missing, vec!["D4", "E4", "F4", "A4", "B4"]);
+
+        // the fifths C and G are both sampled in the first octave only
+        let fifths = Scale::try_new(vec![7, 5]).unwrap();
+        assert!(instrument.missing_samples_for(&fifths, c4.clone(), 1).unwrap().is_empty());
+        let missing: Vec<String> = instrument.missing_samples_for(&fifths, c4, 2).unwrap().iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(missing, vec!["C5", "G5"]);
+    }
+}
+
+#[cfg(test)]
+mod fold_into_range_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use super::*;
+
+    #[test]
+    fn test_range() {
+        let (lowest, highest) = test_instrument("piano").range().unwrap();
+        assert_eq!(lowest.to_string(), "C4");
+        assert_eq!(highest.to_string(), "G4");
+    }
+
+    #[test]
+    fn test_fold() {
+        let folded = test_instrument("piano").fold_into_range(Pitch::new_without_accidental(PitchName::E, 1)).unwrap();
+        assert_eq!(folded.to_string(), "E4");
+        let folded = test_instrument("piano").fold_into_range(Pitch::new(PitchName::F, 7, Accidental::Sharp)).unwrap();
+        assert_eq!(folded.to_string(), "F#4");
+        let folded = test_instrument("piano").fold_into_range(Pitch::new_without_accidental(PitchName::D, 4)).unwrap();
+        assert_eq!(folded.to_string(), "D4");
+    }
+
+    #[test]
+    fn test_no_octave_in_range() {
+        assert!(test_instrument("piano").fold_into_range(Pitch::new_without_accidental(PitchName::A, 1)).is_err());
+    }
+}
+
+#[cfg(test)]
+mod load_sample_tests {
+    use super::*;
+
+    #[test]
+    fn test_sidecar() {
+        let path = Path::new("./resources/test_samples/piano/C4.flac");
+        assert_eq!(read_loop_points(path).unwrap(), Some((400, 1600)));
+        assert_eq!(load_sample(path).unwrap().loop_region, (400, 1600));
+    }
+
+    #[test]
+    fn test_fallback() {
+        let path = Path::new("./resources/test_samples/piano/G4.flac");
+        assert_eq!(read_loop_points(path).unwrap(), None);
+        let sample = load_sample(path).unwrap();
+        assert_eq!(sample.loop_region, find_loop_region(&sample.samples));
+    }
+
+    #[test]
+    fn test_invalid_sidecar() {
+        let dir = env::temp_dir().join("ecotonova_load_sample_tests");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("C4.flac");
+        fs::copy("./resources/test_samples/piano/C4.flac", &path).unwrap();
+        fs::write(dir.join("C4.loop"), "400").unwrap();
+        assert!(load_sample(&path).is_err());
+        fs::write(dir.join("C4.loop"), "400 3000").unwrap();
+        assert!(load_sample(&path).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod generate_pitch_samples_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_render_pitch_duration() {
+        let instrument = test_instrument("piano");
+        let result = render_pitch(instrument, Pitch::new_without_accidental(PitchName::C, 4)).unwrap();
+        assert_eq!(result.rate, 8000);
+        assert_eq!(result.duration.as_secs_f64(), result.samples.len() as f64 / result.rate as f64);
+        assert_eq!(result.duration, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_exact_pitch_is_not_shifted() {
+        let instrument = test_instrument("piano");
+        let generated = generate_pitch_samples(instrument, Pitch::new_without_accidental(PitchName::C, 4)).unwrap();
+        let decoded = decode_sample_file(Path::new("./resources/test_samples/piano/C4.flac")).unwrap();
+        assert_eq!(generated, decoded);
+    }
+}
+
+#[cfg(test)]
+mod render_for_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_length() {
+        let instrument = test_instrument("piano");
+        let pitch = Pitch::new_without_accidental(PitchName::C, 4);
+        // the sample is 250 ms long
+        let (_, samples) = render_for(instrument.clone(), pitch.clone(), Duration::from_millis(100)).unwrap();
+        assert_eq!(samples.len(), 800);
+        let (_, samples) = render_for(instrument, pitch, Duration::from_secs(1)).unwrap();
+        assert_eq!(samples.len(), 8000);
+        // sustained by looping, not padded with silence
+        assert!(samples[7000..].iter().any(|sample| sample.abs() > 0.1));
+    }
+}
+
+#[cfg(test)]
+mod best_register_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use super::*;
+
+    #[test]
+    fn test_sampled_class() {
+        // C2, C4 and C6 are sampled
+        let pitch = test_instrument("registers").best_register(PitchClass::new(PitchName::C, Accidental::None)).unwrap();
+        assert_eq!(pitch.to_string(), "C4");
+        let pitch = test_instrument("registers").best_register(PitchClass::new(PitchName::B, Accidental::Sharp)).unwrap();
+        assert_eq!(pitch.to_string(), "C4");
+    }
+
+    #[test]
+    fn test_unsampled_class() {
+        let pitch = test_instrument("registers").best_register(PitchClass::new(PitchName::D, Accidental::Flat)).unwrap();
+        assert_eq!(pitch.to_string(), "Db4");
+    }
+}
+
+#[cfg(test)]
+mod fit_melody_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use super::*;
+
+    #[test]
+    fn test_too_low() {
+        let quarter = Duration::from_millis(500);
+        let melody = Melody::new(vec![
+            Note::new(Pitch::new_without_accidental(PitchName::D, 1), quarter),
+            Note::rest(quarter),
+            Note::new(Pitch::new(PitchName::F, 1, Accidental::Sharp), quarter),
+        ]);
+        let fitted = test_instrument("piano").fit_melody(&melody).unwrap();
+        let pitches: Vec<Option<String>> = fitted.notes.iter().map(|note| note.pitch.as_ref().map(|pitch| pitch.to_string())).collect();
+        assert_eq!(pitches, vec![Some("D4".to_string()), None, Some("F#4".to_string())]);
+    }
+
+    #[test]
+    fn test_in_range() {
+        let melody = Melody::new(vec![Note::new(Pitch::new_without_accidental(PitchName::E, 4), Duration::from_millis(500))]);
+        assert_eq!(test_instrument("piano").fit_melody(&melody).unwrap(), melody);
+        assert_eq!(test_instrument("piano").fit_melody(&Melody::default()).unwrap(), Melody::default());
+    }
+}
+
+#[cfg(test)]
+mod render_preview_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_length() {
+        let instrument = test_instrument("piano");
+        let pitch = Pitch::new_without_accidental(PitchName::C, 4);
+        let (sample_rate, samples) = render_preview(instrument, pitch, Duration::from_millis(100)).unwrap();
+        assert_eq!(sample_rate, 8000);
+        assert_eq!(samples.len(), 800);
+        assert_eq!(samples[799], 0.0);
+    }
+}
+
+#[cfg(test)]
+mod samples_dir_tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence() {
+        let explicit = Path::new("/explicit/samples");
+        let env_dir = OsString::from("/env/samples");
+        assert_eq!(resolve_samples_dir(None, None), PathBuf::from(DEFAULT_SAMPLES_DIR));
+        assert_eq!(resolve_samples_dir(None, Some(env_dir.clone())), PathBuf::from("/env/samples"));
+        assert_eq!(resolve_samples_dir(Some(explicit), None), PathBuf::from("/explicit/samples"));
+        assert_eq!(resolve_samples_dir(Some(explicit), Some(env_dir)), PathBuf::from("/explicit/samples"));
+    }
+
+    #[test]
+    fn test_sample_folder_path_in() {
+        let folder = Instrument::SalamanderGrandPiano.sample_folder_path_in(Path::new("/samples"));
+        assert_eq!(folder, PathBuf::from("/samples/salamander_grand_piano"));
+    }
+}
+
+#[cfg(test)]
+mod clamp_audible_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_boundaries() {
+        assert_eq!(clamp_audible(MIN_AUDIBLE_HERTZ), MIN_AUDIBLE_HERTZ);
+        assert_eq!(clamp_audible(MAX_AUDIBLE_HERTZ), MAX_AUDIBLE_HERTZ);
+        assert_eq!(clamp_audible(19.9), MIN_AUDIBLE_HERTZ);
+        assert_eq!(clamp_audible(20_000.1), MAX_AUDIBLE_HERTZ);
+        assert_eq!(clamp_audible(0.0), MIN_AUDIBLE_HERTZ);
+    }
+
+    #[test]
+    fn test_in_range_pitch() {
+        let hertz = Pitch::new_without_accidental(PitchName::A, 4).to_hertz();
+        assert_eq!(clamp_audible(hertz), hertz);
+        assert!(is_audible(hertz));
+    }
+
+    #[test]
+    fn test_out_of_range_pitch() {
+        let hertz = Pitch::new_without_accidental(PitchName::A, -1).to_hertz();
+        assert!(!is_audible(hertz));
+        assert_eq!(clamp_audible(hertz), MIN_AUDIBLE_HERTZ);
+    }
+
+    #[test]
+    fn test_skip_inaudible_pitch() {
+        let pitch = Pitch::new_without_accidental(PitchName::A, -1);
+        let hertz = pitch.to_hertz();
+        let outcome = Instrument::SalamanderGrandPiano.play_with_policy(pitch, InaudiblePolicy::Skip).unwrap();
+        assert_eq!(outcome, PlayOutcome::Skipped(hertz));
+    }
+}
+
+#[cfg(test)]
+mod render_noise_hit_tests {
+    use crate::instruments::dsp::peak_amplitude;
+    use super::*;
+
+    #[test]
+    fn test_enveloped_noise() {
+        let env = Envelope {
+            attack: Duration::from_millis(10),
+            release: Duration::from_millis(50),
+            curve: FadeCurve::Linear,
+        };
+        let samples = render_noise_hit(Duration::from_millis(100), 1000, &env);
+        assert_eq!(samples.len(), 100);
+        assert_eq!(samples[0], 0.0);
+        assert_eq!(samples[99], 0.0);
+        // the body between the attack and the release is at full level, the release tail is quieter
+        assert!(peak_amplitude(&samples[10..50]) > 0.9);
+        assert!(peak_amplitude(&samples[90..]) < 0.2);
+        assert!(samples.iter().zip(render_noise_hit(Duration::from_millis(100), 1000, &Envelope::default()))
+            .all(|(shaped, raw)| shaped.abs() <= raw.abs()));
+    }
+}
+
+#[cfg(test)]
+mod render_drone_tests {
+    use super::*;
+
+    #[test]
+    fn test_length_and_seams() {
+        let duration = Duration::from_secs(1);
+        let (sample_rate, samples) = render_drone(test_instrument("piano"), Pitch::try_from("C4").unwrap(), duration).unwrap();
+        assert_eq!(samples.len(), duration_to_samples(duration, sample_rate));
+        // C4.loop loops 400..1600 of the 2000 samples, so the loop restarts every 1200 samples after the first 400
+        let (loop_start, loop_end) = read_loop_points(Path::new("./resources/test_samples/piano/C4.flac")).unwrap().unwrap();
+        assert!(samples.len() > 2000);
+        let seams: Vec<usize> = (1..).map(|n| loop_start + n * (loop_end - loop_start)).take_while(|seam| *seam < samples.len()).collect();
+        assert!(seams.len() > 1);
+        for seam in seams {
+            let jump = (samples[seam] - samples[seam - 1]).abs();
+            assert!(jump < 0.15, "jump of {} at the seam at {}", jump, seam);
+        }
+    }
+}
+
+#[cfg(test)]
+mod render_note_tests {
+    use crate::instruments::dsp::peak_amplitude;
+    use super::*;
+
+    #[test]
+    fn test_velocity() {
+        let note = |velocity: f32| Note { velocity, ..Note::new(Pitch::try_from("C4").unwrap(), Duration::from_millis(200)) };
+        let (_, full) = render_note(test_instrument("piano"), &note(1.0)).unwrap();
+        let (_, half) = render_note(test_instrument("piano"), &note(0.5)).unwrap();
+        assert_eq!(full.len(), half.len());
+        assert!((peak_amplitude(&half) / peak_amplitude(&full) - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rest() {
+        let (sample_rate, samples) = render_note(test_instrument("piano"), &Note::rest(Duration::from_millis(100))).unwrap();
+        assert_eq!(sample_rate, DEFAULT_SAMPLE_RATE);
+        assert_eq!(samples.len(), duration_to_samples(Duration::from_millis(100), DEFAULT_SAMPLE_RATE));
+        assert!(samples.iter().all(|s| *s == 0.0));
+    }
+}
+
+#[cfg(test)]
+mod render_sequence_tests {
+    use super::*;
+
+    #[test]
+    fn test_rest_is_silence() {
+        let samples = render_rest(Duration::from_millis(500), 1000);
+        assert_eq!(samples.len(), 500);
+        assert!(samples.iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_sequence_of_rests() {
+        let notes = vec![
+            Note::rest(Duration::from_millis(250)),
+            Note::rest(Duration::from_millis(500)),
+        ];
+        let (sample_rate, samples) = render_sequence(Instrument::SalamanderGrandPiano, &notes).unwrap();
+        assert_eq!(sample_rate, DEFAULT_SAMPLE_RATE);
+        assert_eq!(samples.len(), duration_to_samples(Duration::from_millis(750), DEFAULT_SAMPLE_RATE));
+        assert!(samples.iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_mixed_sample_rates() {
+        // C4 is sampled at 8000 Hz and G4 at 16000 Hz
+        let instrument = test_instrument("mixed_rates");
+        let notes = vec![
+            Note::new(Pitch::try_from("C4").unwrap(), Duration::from_millis(250)),
+            Note::new(Pitch::try_from("G4").unwrap(), Duration::from_millis(250)),
+        ];
+        let (sample_rate, samples) = render_sequence(instrument, &notes).unwrap();
+        assert_eq!(sample_rate, 8000);
+        assert_eq!(samples.len(), 4000);
+        // a 392 Hz tone crosses zero about 196 times in 250 ms at the right speed
+        let crossings = samples[2000..].windows(2).filter(|pair| pair[0].signum() != pair[1].signum()).count();
+        assert!((180..=210).contains(&crossings), "{} zero crossings", crossings);
+    }
+}
@@ -0,0 +1,722 @@
+use std::fmt::{Display, Formatter};
+use std::sync::LazyLock;
+use regex::Regex;
+use crate::theory::interval::{Interval, IntervalQuality, IntervalStep};
+use crate::theory::pitch::{Accidental, Pitch, PitchName};
+use crate::theory::pitch_class::PitchClass;
+
+/// The octave of the root of a chord parsed from a symbol, which does not name an octave.
+const SYMBOL_ROOT_OCTAVE: i8 = 4;
+
+/// The pattern of a chord symbol like "Bbm7": the root letter, its accidental and the quality suffix.
+static CHORD_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([A-G])(#{1,2}|b{1,2})?(.*)$").unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChordQuality {
+    Major,
+    Minor,
+    Augmented,
+    Diminished,
+    DominantSeventh,
+    MajorSeventh,
+    MinorSeventh,
+    HalfDiminishedSeventh,
+    DiminishedSeventh,
+}
+
+impl ChordQuality {
+    /// The chord tones above the root, as pairs of letter steps and semitones.
+    fn structure(&self) -> Vec<(i16, i16)> {
+        match self {
+            ChordQuality::Major => vec![(2, 4), (4, 7)],
+            ChordQuality::Minor => vec![(2, 3), (4, 7)],
+            ChordQuality::Augmented => vec![(2, 4), (4, 8)],
+            ChordQuality::Diminished => vec![(2, 3), (4, 6)],
+            ChordQuality::DominantSeventh => vec![(2, 4), (4, 7), (6, 10)],
+            ChordQuality::MajorSeventh => vec![(2, 4), (4, 7), (6, 11)],
+            ChordQuality::MinorSeventh => vec![(2, 3), (4, 7), (6, 10)],
+            ChordQuality::HalfDiminishedSeventh => vec![(2, 3), (4, 6), (6, 10)],
+            ChordQuality::DiminishedSeventh => vec![(2, 3), (4, 6), (6, 9)],
+        }
+    }
+
+    /// The suffix of the quality in a chord symbol, e.g. "m7" in "Am7".
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ChordQuality::Major => "",
+            ChordQuality::Minor => "m",
+            ChordQuality::Augmented => "aug",
+            ChordQuality::Diminished => "dim",
+            ChordQuality::DominantSeventh => "7",
+            ChordQuality::MajorSeventh => "maj7",
+            ChordQuality::MinorSeventh => "m7",
+            ChordQuality::HalfDiminishedSeventh => "m7b5",
+            ChordQuality::DiminishedSeventh => "dim7",
+        }
+    }
+}
+
+impl TryFrom<String> for ChordQuality {
+    type Error = ();
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "" => Ok(ChordQuality::Major),
+            "m" => Ok(ChordQuality::Minor),
+            "aug" => Ok(ChordQuality::Augmented),
+            "dim" => Ok(ChordQuality::Diminished),
+            "7" => Ok(ChordQuality::DominantSeventh),
+            "maj7" => Ok(ChordQuality::MajorSeventh),
+            "m7" => Ok(ChordQuality::MinorSeventh),
+            "m7b5" => Ok(ChordQuality::HalfDiminishedSeventh),
+            "dim7" => Ok(ChordQuality::DiminishedSeventh),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chord {
+    root: Pitch,
+    quality: ChordQuality,
+    pitches: Vec<Pitch>,
+}
+
+impl Display for Chord {
+    /// Writes the chord symbol, e.g. "Dm" or "Cmaj7". The octave of the root is not part of the symbol.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.root.name, self.root.accidental, self.quality.symbol())
+    }
+}
+
+impl TryFrom<String> for Chord {
+    type Error = ();
+
+    /// Parses a chord symbol, e.g. "Bbm7" or "F#dim", as written by `Display`.
+    ///
+    /// The root is placed in octave 4.
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let captures = CHORD_REGEX.captures(&value).ok_or(())?;
+        let name = captures.get(1).ok_or(())?.as_str();
+        let accidental = captures.get(2).map_or("", |m| m.as_str());
+        let quality = captures.get(3).map_or("", |m| m.as_str());
+        let root = Pitch::new(
+            PitchName::try_from(name.to_string())?,
+            SYMBOL_ROOT_OCTAVE,
+            Accidental::try_from(accidental.to_string())?,
+        );
+        Chord::try_new(root, ChordQuality::try_from(quality.to_string())?)
+    }
+}
+
+impl Chord {
+    /// Creates a chord in root position, stacking its tones above the root.
+    ///
+    /// Fails if a chord tone cannot be spelled with at most a double sharp or double flat.
+    pub fn try_new(root: Pitch, quality: ChordQuality) -> Result<Self, ()> {
+        let mut pitches = vec![root.clone()];
+        for (letter_steps, semitones) in quality.structure() {
+            pitches.push(root.spell_at(letter_steps, semitones)?);
+        }
+        Ok(Self { root, quality, pitches })
+    }
+
+    pub fn root(&self) -> &Pitch {
+        &self.root
+    }
+
+    pub fn quality(&self) -> &ChordQuality {
+        &self.quality
+    }
+
+    /// The pitches of the chord, from the root upwards.
+    pub fn pitches(&self) -> &[Pitch] {
+        &self.pitches
+    }
+
+    /// Gets the shell voicing of the chord: the root, the third and the seventh, omitting the fifth.
+    ///
+    /// A triad has no seventh, so only its root and third are returned.
+    pub fn shell_voicing(&self) -> Vec<Pitch> {
+        self.pitches.iter()
+            .enumerate()
+            .filter(|(i, _)| *i != 2)
+            .map(|(_, pitch)| pitch.clone())
+            .collect()
+    }
+
+    /// Gets the tritone substitute of the chord: the dominant seventh chord a tritone away from the root (Db7 for G7).
+    ///
+    /// Both chords share their guide tones, the third and the seventh, with the roles swapped (B and F in G7 are F and Cb in Db7).
+    /// The root is spelled with as few accidentals as possible, see `Pitch::simplify`.
+    pub fn tritone_sub(&self) -> Chord {
+        let root = self.root.transpose_semitones(6).simplify();
+        Chord::try_new(root, ChordQuality::DominantSeventh).unwrap()
+    }
+
+    /// Gets the quality of the fifth above the root: perfect, diminished (b5, e.g. in m7b5) or augmented (#5, e.g. in aug).
+    ///
+    /// # Returns
+    ///
+    /// The quality, or `None` if the chord has no fifth.
+    pub fn fifth_quality(&self) -> Option<IntervalQuality> {
+        let fifth = self.pitches.get(2)?;
+        Interval::new(self.root.clone(), fifth.clone()).get_quality().ok()
+    }
+
+    /// Voices an inversion of the chord: the chord tone `n` is in the bass (0 for the root, 1 for the third, ...)
+    /// and the tones below it in root position are moved up an octave, e.g. E4 G4 C5 for the first inversion of C4 E4 G4.
+    ///
+    /// # Returns
+    ///
+    /// The pitches from the bass upwards, or `None` if the chord has no tone `n`.
+    pub fn inversion(&self, n: usize) -> Option<Vec<Pitch>> {
+        if n >= self.pitches.len() {
+            return None;
+        }
+        let mut pitches = self.pitches[n..].to_vec();
+        for pitch in &self.pitches[..n] {
+            let mut raised = pitch.clone();
+            raised.octave = raised.octave.checked_add(1)?;
+            pitches.push(raised);
+        }
+        Some(pitches)
+    }
+
+    /// Voices every inversion of the chord, from root position up to the seventh in the bass, see `inversion`.
+    pub fn all_inversions(&self) -> Vec<Vec<Pitch>> {
+        (0..self.pitches.len()).filter_map(|n| self.inversion(n)).collect()
+    }
+
+    /// Respells the root with as few accidentals as possible, see `Pitch::simplify`, and the other tones with it.
+    ///
+    /// The chord keeps its sound, e.g. B# major becomes C major.
+    pub fn prefer_simpler_root(&self) -> Chord {
+        let root = self.root.simplify();
+        if root.name == self.root.name {
+            return self.clone();
+        }
+        Chord::try_new(root, self.quality.clone()).unwrap_or_else(|_| self.clone())
+    }
+
+    /// Respells the chord with as few accidentals as possible over all of its tones, e.g. G# augmented (G# B# D##)
+    /// becomes Ab augmented (Ab C E). Unlike `prefer_simpler_root`, a double sharp or flat on an upper tone counts too.
+    ///
+    /// Every enharmonic spelling of the root is tried, see `Pitch::enharmonic_equivalents`, and the tones are stacked
+    /// on it in thirds, so each tone keeps a letter of its own. On a tie the current spelling is kept.
+    pub fn best_spelling(&self) -> Chord {
+        let complexity = |chord: &Chord| -> u32 {
+            chord.pitches.iter().map(|pitch| pitch.accidental_complexity() as u32).sum()
+        };
+        let mut best = self.clone();
+        for root in self.root.enharmonic_equivalents() {
+            if let Ok(chord) = Chord::try_new(root, self.quality.clone()) {
+                if complexity(&chord) < complexity(&best) {
+                    best = chord;
+                }
+            }
+        }
+        best
+    }
+
+    /// Gets the chord a dominant seventh chord resolves to.
+    ///
+    /// The dominant seventh resolves to the major triad a perfect fourth above its root (G7 to C),
+    /// so that its leading tone (the third) moves up to the new root and its seventh moves down to the new third.
+    ///
+    /// # Returns
+    ///
+    /// The tonic chord, or `None` if the chord is not a dominant seventh.
+    pub fn resolution(&self) -> Option<Chord> {
+        if self.quality != ChordQuality::DominantSeventh {
+            return None;
+        }
+        let tonic = self.root.spell_at(3, 5).ok()?;
+        Chord::try_new(tonic, ChordQuality::Major).ok()
+    }
+
+    /// Finds which inversion a voicing of a chord is in, comparing the lowest pitch with the root, see `find_root_by_thirds`.
+    ///
+    /// # Returns
+    ///
+    /// 0 if the root is in the bass (root position), 1 if the third is, 2 if the fifth is and 3 if the seventh is,
+    /// or `None` if the pitches do not stack as thirds.
+    pub fn inversion_of(pitches: &[Pitch]) -> Option<usize> {
+        let root = PitchClass::from(find_root_by_thirds(pitches)?).chromatic_index();
+        let bass = PitchClass::from(pitches.iter().min()?.clone()).chromatic_index();
+        match (bass + 12 - root) % 12 {
+            0 => Some(0),
+            3 | 4 => Some(1),
+            6..=8 => Some(2),
+            9..=11 => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// Transposes a chord symbol by a number of semitones, e.g. "Cmaj7" up 2 semitones is "Dmaj7".
+///
+/// The new root is spelled with a natural where possible, as on a lead sheet, and black keys with a sharp or a flat.
+///
+/// # Arguments
+///
+/// * `symbol` - The chord symbol, see `TryFrom<String> for Chord`
+/// * `semitones` - The number of semitones to move the root, negative to move it down
+/// * `prefer_flats` - Whether a black key root is spelled with a flat (Bb) instead of a sharp (A#)
+///
+/// # Returns
+///
+/// The transposed symbol, or an error if the symbol cannot be parsed or the transposed chord cannot be spelled.
+pub fn transpose_chord_symbol(symbol: &str, semitones: i32, prefer_flats: bool) -> Result<String, ()> {
+    let chord = Chord::try_from(symbol.to_string())?;
+    let value = f32::from(chord.root.clone()) + semitones as f32 * f32::from(IntervalStep::Half);
+    let mut root = Pitch::try_from(value)?;
+    if prefer_flats && root.accidental == Accidental::Sharp {
+        root = root.spell_at(1, 0)?;
+    }
+    Ok(Chord::try_new(root, chord.quality)?.to_string())
+}
+
+/// Checks whether three pitches form a recognized triad.
+///
+/// The pitches are reduced to pitch classes first, so the triad is recognized in any inversion
+/// and in any octave. The spelling of the pitches is ignored.
+///
+/// # Arguments
+///
+/// * `pitches` - The three pitches to check
+///
+/// # Returns
+///
+/// The root and the quality ("major", "minor", "augmented" or "diminished") of the triad,
+/// or `None` if the pitches do not form one of these triads.
+pub fn is_triad(pitches: &[Pitch]) -> Option<(Pitch, String)> {
+    if pitches.len() != 3 {
+        return None;
+    }
+    let classes: Vec<u8> = pitches.iter().map(|pitch| PitchClass::from(pitch.clone()).chromatic_index()).collect();
+    for (i, root) in pitches.iter().enumerate() {
+        // semitones of the other two pitch classes above the candidate root
+        let mut above: Vec<u8> = classes.iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, class)| (class + 12 - classes[i]) % 12)
+            .collect();
+        above.sort();
+        let quality = match (above[0], above[1]) {
+            (4, 7) => "major",
+            (3, 7) => "minor",
+            (4, 8) => "augmented",
+            (3, 6) => "diminished",
+            _ => continue,
+        };
+        return Some((root.clone(), quality.to_string()));
+    }
+    None
+}
+
+
+/// Finds the root of a chord from its pitches, in any inversion and voicing, by trying every pitch as the root
+/// and checking whether the other pitches stack as thirds above it.
+///
+/// Above the candidate root there may be at most one third (3 or 4 semitones), one fifth (6 to 8 semitones)
+/// and one seventh (9 to 11 semitones), and no other pitch class. Thirds and perfect fifths count more than altered fifths
+/// and sevenths, so the candidate with the most conventional structure wins; on a tie the earlier pitch wins.
+///
+/// # Returns
+///
+/// The pitch the root was found in, or `None` if no pitch has all the other pitches stacked as thirds above it.
+pub fn find_root_by_thirds(pitches: &[Pitch]) -> Option<Pitch> {
+    let mut classes: Vec<u8> = pitches.iter().map(|pitch| PitchClass::from(pitch.clone()).chromatic_index()).collect();
+    let mut best: Option<(u8, u32)> = None;
+    let candidates = classes.clone();
+    classes.sort();
+    classes.dedup();
+    'candidates: for root in candidates {
+        let mut score = 0;
+        // the number of pitch classes on the third, the fifth and the seventh above the root
+        let mut slots = [0; 3];
+        for class in &classes {
+            let (slot, points) = match (class + 12 - root) % 12 {
+                0 => continue,
+                3 | 4 => (0, 2),
+                7 => (1, 2),
+                6 | 8 => (1, 1),
+                9..=11 => (2, 1),
+                _ => continue 'candidates,
+            };
+            slots[slot] += 1;
+            if slots[slot] > 1 {
+                continue 'candidates;
+            }
+            score += points;
+        }
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((root, score));
+        }
+    }
+    let (root, _) = best?;
+    pitches.iter().find(|pitch| PitchClass::from((*pitch).clone()).chromatic_index() == root).cloned()
+}
+
+#[cfg(test)]
+mod chord_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use crate::theory::test_helpers::names;
+    use super::*;
+
+    #[test]
+    fn test_pitches() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert_eq!(names(Chord::try_new(c4.clone(), ChordQuality::Major).unwrap().pitches()), vec!["C4", "E4", "G4"]);
+        assert_eq!(names(Chord::try_new(c4.clone(), ChordQuality::Diminished).unwrap().pitches()), vec!["C4", "Eb4", "Gb4"]);
+        assert_eq!(names(Chord::try_new(c4.clone(), ChordQuality::HalfDiminishedSeventh).unwrap().pitches()), vec!["C4", "Eb4", "Gb4", "Bb4"]);
+        assert_eq!(names(Chord::try_new(c4, ChordQuality::DiminishedSeventh).unwrap().pitches()), vec!["C4", "Eb4", "Gb4", "Bbb4"]);
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        assert_eq!(names(Chord::try_new(a4, ChordQuality::MajorSeventh).unwrap().pitches()), vec!["A4", "C#5", "E5", "G#5"]);
+    }
+
+    #[test]
+    fn test_resolution() {
+        let g7 = Chord::try_new(Pitch::new_without_accidental(PitchName::G, 3), ChordQuality::DominantSeventh).unwrap();
+        let tonic = g7.resolution().unwrap();
+        assert_eq!(tonic.quality(), &ChordQuality::Major);
+        assert_eq!(names(tonic.pitches()), vec!["C4", "E4", "G4"]);
+        // the leading tone moves up a half step to the root, the seventh down a half step to the third
+        assert_eq!(f32::from(tonic.pitches()[0].clone()) - f32::from(g7.pitches()[1].clone()), 0.5);
+        assert_eq!(f32::from(g7.pitches()[3].clone()) - f32::from(tonic.pitches()[1].clone()), 0.5);
+    }
+
+    #[test]
+    fn test_prefer_simpler_root() {
+        let b_sharp = Chord::try_new(Pitch::new(PitchName::B, 3, Accidental::Sharp), ChordQuality::Major).unwrap();
+        assert_eq!(names(b_sharp.pitches()), vec!["B#3", "D##4", "F##4"]);
+        let c = b_sharp.prefer_simpler_root();
+        assert_eq!(names(c.pitches()), vec!["C4", "E4", "G4"]);
+        assert_eq!(c.pitches(), b_sharp.pitches());
+
+        let e_flat = Chord::try_new(Pitch::new(PitchName::E, 4, Accidental::Flat), ChordQuality::Minor).unwrap();
+        assert_eq!(names(e_flat.prefer_simpler_root().pitches()), vec!["Eb4", "Gb4", "Bb4"]);
+    }
+
+    #[test]
+    fn test_shell_voicing() {
+        let c = Pitch::new_without_accidental(PitchName::C, 4);
+        let shell = Chord::try_new(c.clone(), ChordQuality::MajorSeventh).unwrap().shell_voicing();
+        let names: Vec<String> = shell.iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(names, vec!["C4", "E4", "B4"]);
+        let shell = Chord::try_new(c, ChordQuality::Minor).unwrap().shell_voicing();
+        let names: Vec<String> = shell.iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(names, vec!["C4", "Eb4"]);
+    }
+
+    #[test]
+    fn test_no_resolution() {
+        let c = Chord::try_new(Pitch::new_without_accidental(PitchName::C, 4), ChordQuality::Major).unwrap();
+        assert!(c.resolution().is_none());
+    }
+}
+
+#[cfg(test)]
+mod tritone_sub_tests {
+    use super::*;
+
+    #[test]
+    fn test_g7() {
+        let g7 = Chord::try_from("G7".to_string()).unwrap();
+        let sub = g7.tritone_sub();
+        assert_eq!(sub.to_string(), "Db7");
+        // the third and the seventh of one chord are the seventh and the third of the other
+        let guide_tones = |chord: &Chord| -> Vec<PitchClass> {
+            vec![PitchClass::from(chord.pitches()[1].clone()), PitchClass::from(chord.pitches()[3].clone())]
+        };
+        assert_eq!(guide_tones(&g7), guide_tones(&sub).into_iter().rev().collect::<Vec<_>>());
+        assert_eq!(sub.tritone_sub().to_string(), "G7");
+    }
+
+    #[test]
+    fn test_other_roots() {
+        assert_eq!(Chord::try_from("C7".to_string()).unwrap().tritone_sub().to_string(), "Gb7");
+        assert_eq!(Chord::try_from("E7".to_string()).unwrap().tritone_sub().to_string(), "Bb7");
+        assert_eq!(Chord::try_from("Cmaj7".to_string()).unwrap().tritone_sub().to_string(), "Gb7");
+    }
+}
+
+#[cfg(test)]
+mod fifth_quality_tests {
+    use super::*;
+
+    fn fifth_quality(symbol: &str) -> Option<IntervalQuality> {
+        Chord::try_from(symbol.to_string()).unwrap().fifth_quality()
+    }
+
+    #[test]
+    fn test_fifth_quality() {
+        assert_eq!(fifth_quality("C"), Some(IntervalQuality::Perfect));
+        assert_eq!(fifth_quality("G7"), Some(IntervalQuality::Perfect));
+        assert_eq!(fifth_quality("Bm7b5"), Some(IntervalQuality::Diminished));
+        assert_eq!(fifth_quality("Ebdim7"), Some(IntervalQuality::Diminished));
+        assert_eq!(fifth_quality("Caug"), Some(IntervalQuality::Augmented));
+        assert_eq!(fifth_quality("F#aug"), Some(IntervalQuality::Augmented));
+    }
+}
+
+#[cfg(test)]
+mod inversion_tests {
+    use crate::theory::pitch::PitchName;
+    use crate::theory::test_helpers::names;
+    use super::*;
+
+    #[test]
+    fn test_triad() {
+        let chord = Chord::try_new(Pitch::new_without_accidental(PitchName::C, 4), ChordQuality::Major).unwrap();
+        let inversions = chord.all_inversions();
+        assert_eq!(inversions.len(), 3);
+        assert_eq!(names(&inversions[0]), vec!["C4", "E4", "G4"]);
+        assert_eq!(names(&inversions[1]), vec!["E4", "G4", "C5"]);
+        assert_eq!(names(&inversions[2]), vec!["G4", "C5", "E5"]);
+        assert!(inversions.iter().enumerate().all(|(n, pitches)| Chord::inversion_of(pitches) == Some(n)));
+    }
+
+    #[test]
+    fn test_seventh_chord() {
+        let chord = Chord::try_from("G7".to_string()).unwrap();
+        let inversions = chord.all_inversions();
+        assert_eq!(inversions.len(), 4);
+        assert_eq!(names(&inversions[3]), vec!["F5", "G5", "B5", "D6"]);
+        assert_eq!(chord.inversion(4), None);
+    }
+}
+
+#[cfg(test)]
+mod symbol_tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for symbol in ["Cmaj7", "G7", "Dm", "Bbm7", "F#dim", "Ebaug", "Bm7b5", "C#dim7", "A"] {
+            let chord = Chord::try_from(symbol.to_string()).unwrap();
+            assert_eq!(chord.to_string(), symbol);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let chord = Chord::try_from("G7".to_string()).unwrap();
+        assert_eq!(chord.quality(), &ChordQuality::DominantSeventh);
+        let names: Vec<String> = chord.pitches().iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(names, vec!["G4", "B4", "D5", "F5"]);
+    }
+
+    #[test]
+    fn test_display() {
+        let chord = Chord::try_new(Pitch::new_without_accidental(PitchName::C, 2), ChordQuality::MajorSeventh).unwrap();
+        assert_eq!(chord.to_string(), "Cmaj7");
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Chord::try_from("H7".to_string()).is_err());
+        assert!(Chord::try_from("Csus4".to_string()).is_err());
+        assert!(Chord::try_from("".to_string()).is_err());
+    }
+}
+
+#[cfg(test)]
+mod find_root_by_thirds_tests {
+    use super::*;
+
+    #[test]
+    fn test_inverted_triad() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::E, 4),
+            Pitch::new_without_accidental(PitchName::G, 4),
+            Pitch::new_without_accidental(PitchName::C, 5),
+        ];
+        assert_eq!(find_root_by_thirds(&pitches), Some(Pitch::new_without_accidental(PitchName::C, 5)));
+    }
+
+    #[test]
+    fn test_seventh_chord() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::G, 3),
+            Pitch::new_without_accidental(PitchName::B, 3),
+            Pitch::new_without_accidental(PitchName::D, 4),
+            Pitch::new_without_accidental(PitchName::F, 4),
+        ];
+        assert_eq!(find_root_by_thirds(&pitches), Some(Pitch::new_without_accidental(PitchName::G, 3)));
+        let inverted = vec![pitches[3].clone(), pitches[1].clone(), pitches[0].clone()];
+        assert_eq!(find_root_by_thirds(&inverted), Some(Pitch::new_without_accidental(PitchName::G, 3)));
+    }
+
+    #[test]
+    fn test_not_stacked_in_thirds() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new(PitchName::C, 4, Accidental::Sharp),
+            Pitch::new_without_accidental(PitchName::D, 4),
+        ];
+        assert_eq!(find_root_by_thirds(&pitches), None);
+        // a minor and a major seventh above D
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::D, 4),
+            Pitch::new_without_accidental(PitchName::C, 5),
+            Pitch::new(PitchName::C, 5, Accidental::Sharp),
+        ];
+        assert_eq!(find_root_by_thirds(&pitches), None);
+        assert_eq!(find_root_by_thirds(&[]), None);
+    }
+}
+
+#[cfg(test)]
+mod inversion_of_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_triad() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let e4 = Pitch::new_without_accidental(PitchName::E, 4);
+        let g4 = Pitch::new_without_accidental(PitchName::G, 4);
+        let c5 = Pitch::new_without_accidental(PitchName::C, 5);
+        assert_eq!(Chord::inversion_of(&[c4.clone(), e4.clone(), g4.clone()]), Some(0));
+        assert_eq!(Chord::inversion_of(&[e4.clone(), g4.clone(), c5.clone()]), Some(1));
+        assert_eq!(Chord::inversion_of(&[g4.clone(), c5.clone(), Pitch::new_without_accidental(PitchName::E, 5)]), Some(2));
+        // the bass decides, not the order of the pitches
+        assert_eq!(Chord::inversion_of(&[c5, g4, e4]), Some(1));
+    }
+
+    #[test]
+    fn test_seventh_chord() {
+        let pitches: Vec<Pitch> = ["F3", "G3", "B3", "D4"].into_iter().map(|name| Pitch::try_from(name).unwrap()).collect();
+        assert_eq!(Chord::inversion_of(&pitches), Some(3));
+    }
+
+    #[test]
+    fn test_not_a_chord() {
+        let pitches: Vec<Pitch> = ["C4", "C#4", "D4"].into_iter().map(|name| Pitch::try_from(name).unwrap()).collect();
+        assert_eq!(Chord::inversion_of(&pitches), None);
+        assert_eq!(Chord::inversion_of(&[]), None);
+    }
+}
+
+#[cfg(test)]
+mod transpose_chord_symbol_tests {
+    use super::*;
+
+    #[test]
+    fn test_up() {
+        assert_eq!(transpose_chord_symbol("Cmaj7", 2, false), Ok("Dmaj7".to_string()));
+        assert_eq!(transpose_chord_symbol("Am", 1, false), Ok("A#m".to_string()));
+        assert_eq!(transpose_chord_symbol("Am", 1, true), Ok("Bbm".to_string()));
+        assert_eq!(transpose_chord_symbol("G7", 5, true), Ok("C7".to_string()));
+        assert_eq!(transpose_chord_symbol("Bm7b5", 14, false), Ok("C#m7b5".to_string()));
+    }
+
+    #[test]
+    fn test_down() {
+        assert_eq!(transpose_chord_symbol("Dm7", -2, false), Ok("Cm7".to_string()));
+        assert_eq!(transpose_chord_symbol("C", -1, false), Ok("B".to_string()));
+        assert_eq!(transpose_chord_symbol("E7", -3, false), Ok("C#7".to_string()));
+        assert_eq!(transpose_chord_symbol("E7", -3, true), Ok("Db7".to_string()));
+        assert_eq!(transpose_chord_symbol("F#dim", -12, true), Ok("Gbdim".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_symbol() {
+        assert!(transpose_chord_symbol("Hmaj7", 2, false).is_err());
+        assert!(transpose_chord_symbol("Csus9", 2, false).is_err());
+    }
+}
+
+#[cfg(test)]
+mod is_triad_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use super::*;
+
+    #[test]
+    fn test_major_inversion() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::E, 4),
+            Pitch::new_without_accidental(PitchName::G, 4),
+            Pitch::new_without_accidental(PitchName::C, 5),
+        ];
+        let (root, quality) = is_triad(&pitches).unwrap();
+        assert_eq!(root, Pitch::new_without_accidental(PitchName::C, 5));
+        assert_eq!(quality, "major");
+    }
+
+    #[test]
+    fn test_diminished() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new(PitchName::E, 4, Accidental::Flat),
+            Pitch::new(PitchName::G, 4, Accidental::Flat),
+        ];
+        let (root, quality) = is_triad(&pitches).unwrap();
+        assert_eq!(root, Pitch::new_without_accidental(PitchName::C, 4));
+        assert_eq!(quality, "diminished");
+    }
+
+    #[test]
+    fn test_minor_and_augmented() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::A, 3),
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+        ];
+        assert_eq!(is_triad(&pitches).unwrap().1, "minor");
+
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+            Pitch::new(PitchName::G, 4, Accidental::Sharp),
+        ];
+        assert_eq!(is_triad(&pitches).unwrap().1, "augmented");
+    }
+
+    #[test]
+    fn test_not_a_triad() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::D, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+        ];
+        assert_eq!(is_triad(&pitches), None);
+
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+        ];
+        assert_eq!(is_triad(&pitches), None);
+    }
+}
+
+#[cfg(test)]
+mod best_spelling_tests {
+    use crate::theory::test_helpers::names;
+    use super::*;
+
+    #[test]
+    fn test_augmented_triad() {
+        let chord = Chord::try_from("G#aug".to_string()).unwrap();
+        assert_eq!(names(chord.pitches()), vec!["G#4", "B#4", "D##5"]);
+        let best = chord.best_spelling();
+        assert_eq!(names(best.pitches()), vec!["Ab4", "C5", "E5"]);
+        assert_eq!(best.quality(), &ChordQuality::Augmented);
+    }
+
+    #[test]
+    fn test_simple_spelling_is_kept() {
+        let chord = Chord::try_from("Ebm7".to_string()).unwrap();
+        assert_eq!(chord.best_spelling(), chord);
+        let chord = Chord::try_from("C".to_string()).unwrap();
+        assert_eq!(chord.best_spelling(), chord);
+    }
+
+    #[test]
+    fn test_upper_tones_count() {
+        let chord = Chord::try_from("D#dim7".to_string()).unwrap();
+        assert_eq!(names(chord.best_spelling().pitches()), vec!["D#4", "F#4", "A4", "C5"]);
+    }
+}
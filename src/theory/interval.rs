@@ -1,6 +1,9 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use crate::theory::pitch::{Pitch, PitchName};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntervalQuality {
     Perfect,
     Major,
@@ -10,6 +13,7 @@ pub enum IntervalQuality {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntervalStep {
     Half,
     Whole,
@@ -36,12 +40,129 @@ impl From<IntervalStep> for f32 {
     }
 }
 
+/// How stable an interval sounds, as classified in counterpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
+pub enum Consonance {
+    /// Perfect unisons, fifths and octaves
+    PerfectConsonance,
+    /// Major and minor thirds and sixths
+    ImperfectConsonance,
+    /// Seconds, fourths, sevenths and every augmented or diminished interval, e.g. the tritone
+    Dissonance,
+}
+
+/// The reasons the quality of an interval cannot be named.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntervalError {
+    /// The simple interval number is not between 1 and 7.
+    UnsupportedNumber(u8),
+    /// The semitones do not fit any quality of the simple interval number, e.g. a doubly augmented fourth.
+    UnsupportedSemitones { number: u8, semitones: u16 },
+}
+
+impl Display for IntervalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntervalError::UnsupportedNumber(number) => write!(f, "Unsupported interval number {}", number),
+            IntervalError::UnsupportedSemitones { number, semitones } => {
+                write!(f, "No interval quality for a {} of {} semitones", number, semitones)
+            }
+        }
+    }
+}
+
+impl Error for IntervalError {}
+
+/// Calculates the number of semitones of an interval from its number and quality.
+///
+/// # Arguments
+///
+/// * `number` - The interval number, compound numbers add 12 semitones for every octave.
+/// * `quality` - The interval quality.
+///
+/// # Returns
+///
+/// A `u8` representing the number of semitones, or an error if the quality is impossible for the number (e.g. a perfect third).
+pub fn semitones_of(number: u8, quality: &IntervalQuality) -> Result<u8, ()> {
+    if number == 0 {
+        return Err(());
+    }
+    let octaves = ((number - 1) / 7) as i16;
+    let simple_number = (number - 1) % 7 + 1;
+    let semitones: i16 = match (simple_number, quality) {
+        (1, IntervalQuality::Diminished) if octaves == 0 => return Err(()),
+        (1 | 4 | 5, IntervalQuality::Major | IntervalQuality::Minor) => return Err(()),
+        (2 | 3 | 6 | 7, IntervalQuality::Perfect) => return Err(()),
+        (1 | 4 | 5, _) => {
+            let perfect = match simple_number {
+                1 => 0,
+                4 => 5,
+                _ => 7,
+            };
+            match quality {
+                IntervalQuality::Diminished => perfect - 1,
+                IntervalQuality::Augmented => perfect + 1,
+                _ => perfect,
+            }
+        }
+        _ => {
+            let major = match simple_number {
+                2 => 2,
+                3 => 4,
+                6 => 9,
+                _ => 11,
+            };
+            match quality {
+                IntervalQuality::Diminished => major - 2,
+                IntervalQuality::Minor => major - 1,
+                IntervalQuality::Augmented => major + 1,
+                _ => major,
+            }
+        }
+    };
+    // a diminished octave reduces to a diminished unison of -1 semitones
+    u8::try_from(semitones + octaves * 12).map_err(|_| ())
+}
+
+// The shorthand names of the simple intervals, see `Interval::from_name`.
+pub const PERFECT_UNISON: &str = "P1";
+pub const MINOR_SECOND: &str = "m2";
+pub const MAJOR_SECOND: &str = "M2";
+pub const MINOR_THIRD: &str = "m3";
+pub const MAJOR_THIRD: &str = "M3";
+pub const PERFECT_FOURTH: &str = "P4";
+pub const TRITONE: &str = "A4";
+pub const PERFECT_FIFTH: &str = "P5";
+pub const MINOR_SIXTH: &str = "m6";
+pub const MAJOR_SIXTH: &str = "M6";
+pub const MINOR_SEVENTH: &str = "m7";
+pub const MAJOR_SEVENTH: &str = "M7";
+pub const PERFECT_OCTAVE: &str = "P8";
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "IntervalPitches"))]
 pub struct Interval {
     lower: Pitch,
     upper: Pitch,
 }
 
+/// The pitches of a deserialized interval, in either order, which are put in order by `Interval::new`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct IntervalPitches {
+    lower: Pitch,
+    upper: Pitch,
+}
+
+#[cfg(feature = "serde")]
+impl From<IntervalPitches> for Interval {
+    fn from(pitches: IntervalPitches) -> Self {
+        Interval::new(pitches.lower, pitches.upper)
+    }
+}
+
 impl Interval {
     pub fn new(p1: Pitch, p2: Pitch) -> Self {
         return if p1 < p2 {
@@ -51,6 +172,52 @@ impl Interval {
         };
     }
 
+    /// Creates the interval of the given number and quality above a root, e.g. a perfect fifth above C4 is C4-G4.
+    ///
+    /// The upper pitch is spelled by the interval number, so a minor third above C4 is Eb4 (not D#4).
+    /// The root is always the lower pitch, also when the upper pitch sounds the same, e.g. C4-Dbb4 for a diminished second.
+    ///
+    /// # Returns
+    ///
+    /// The interval, or an error if the quality is impossible for the number (e.g. a perfect third, see `semitones_of`)
+    /// or the upper pitch would need more than a double sharp or double flat.
+    pub fn from_parts(number: u8, quality: IntervalQuality, root: Pitch) -> Result<Interval, ()> {
+        let semitones = semitones_of(number, &quality)?;
+        let upper = root.spell_at(number as i16 - 1, semitones as i16)?;
+        // the upper pitch never sounds below the root, as the semitones are not negative
+        Ok(Self { lower: root, upper })
+    }
+
+    /// Creates the interval above a root from its shorthand name, as written by `Display`, e.g. "P5" or "m3",
+    /// see `from_parts` and the named intervals like `PERFECT_FIFTH`.
+    ///
+    /// # Returns
+    ///
+    /// The interval, or an error if the name cannot be parsed or names an impossible interval.
+    pub fn from_name(name: &str, root: Pitch) -> Result<Interval, ()> {
+        let mut chars = name.chars();
+        let quality = match chars.next() {
+            Some('P') => IntervalQuality::Perfect,
+            Some('M') => IntervalQuality::Major,
+            Some('m') => IntervalQuality::Minor,
+            Some('A') => IntervalQuality::Augmented,
+            Some('d') => IntervalQuality::Diminished,
+            _ => return Err(()),
+        };
+        let number = chars.as_str().parse::<u8>().map_err(|_| ())?;
+        Interval::from_parts(number, quality, root)
+    }
+
+    /// The lower pitch of the interval.
+    pub fn lower(&self) -> &Pitch {
+        &self.lower
+    }
+
+    /// The upper pitch of the interval.
+    pub fn upper(&self) -> &Pitch {
+        &self.upper
+    }
+
     /// Calculates the interval number.
     ///
     /// # Arguments
@@ -61,33 +228,20 @@ impl Interval {
     ///
     /// A `u8` representing the interval number.
     pub fn get_number(&self, ignore_octave: bool) -> u8 {
-        let get_position = |pitch: &Pitch| -> u8 {
-            match &pitch.name {
-                PitchName::C => 0,
-                PitchName::D => 1,
-                PitchName::E => 2,
-                PitchName::F => 3,
-                PitchName::G => 4,
-                PitchName::A => 5,
-                PitchName::B => 6,
-            }
-        };
-        let lower_position = get_position(&self.lower);
-        let upper_position = get_position(&self.upper);
-        let octave_diff = (self.upper.octave - self.lower.octave).abs() as u8 * 7;
+        let steps = self.get_number_of_letter_steps();
         if ignore_octave {
-            if upper_position < lower_position {
-                upper_position + 7 - lower_position + 1
-            } else {
-                upper_position - lower_position + 1
-            }
+            (steps % 7 + 1) as u8
         } else {
-            upper_position - lower_position + 1 + octave_diff
+            (steps + 1) as u8
         }
     }
 
     /// Calculates the semitones between two pitches.
     ///
+    /// When the octave is ignored, the semitones are reduced modulo 12, however many octaves the interval spans,
+    /// so that a compound interval reports the semitones of its simple form. A perfect octave or any multiple of it
+    /// reduces to 0 like the unison, matching `get_number(true)` of 1. Only an augmented seventh keeps its 12 semitones.
+    ///
     /// # Arguments
     ///
     /// * `ignore_octave` - A `bool` that indicates if the octave should be ignored.
@@ -98,23 +252,41 @@ impl Interval {
     pub fn get_number_of_semitones(&self, ignore_octave: bool) -> u16 {
         let semitones = ((f32::from(self.upper.clone()) - f32::from(self.lower.clone())) / f32::from(IntervalStep::Half)) as u16;
         if ignore_octave {
-            let octave_diff = (self.upper.octave - self.lower.octave).abs() as f32;
-            if semitones > 12 {
-                semitones - (octave_diff * 12.0) as u16
+            let reduced = semitones % 12;
+            if reduced == 0 && self.get_number(true) == 7 {
+                12
             } else {
-                semitones
+                reduced
             }
         } else {
             semitones
         }
     }
 
+    /// Calculates the number of letter names from the lower to the upper pitch, ignoring accidentals.
+    fn get_number_of_letter_steps(&self) -> u16 {
+        let get_position = |pitch: &Pitch| -> i16 {
+            let position = match &pitch.name {
+                PitchName::C => 0,
+                PitchName::D => 1,
+                PitchName::E => 2,
+                PitchName::F => 3,
+                PitchName::G => 4,
+                PitchName::A => 5,
+                PitchName::B => 6,
+            };
+            position + pitch.octave as i16 * 7
+        };
+        (get_position(&self.upper) - get_position(&self.lower)).unsigned_abs()
+    }
+
     /// Calculates the interval quality.
     ///
     /// # Returns
     ///
-    /// A `IntervalQuality` representing the interval quality.
-    pub fn get_quality(&self) -> Result<IntervalQuality, ()> {
+    /// A `IntervalQuality` representing the interval quality, or an error if the semitones fit no quality of the number,
+    /// e.g. a doubly augmented interval.
+    pub fn get_quality(&self) -> Result<IntervalQuality, IntervalError> {
         let number = self.get_number(true);
         let semitones = self.get_number_of_semitones(true);
         let quality = match number {
@@ -122,7 +294,9 @@ impl Interval {
                 return match semitones {
                     0 => Ok(IntervalQuality::Perfect),
                     1 => Ok(IntervalQuality::Augmented),
-                    _ => Err(()),
+                    // a diminished octave reduces to one semitone below the unison, which wraps around to 11
+                    11 => Ok(IntervalQuality::Diminished),
+                    _ => Err(IntervalError::UnsupportedSemitones { number, semitones }),
                 }
             }
             2 => {
@@ -131,7 +305,7 @@ impl Interval {
                     1 => Ok(IntervalQuality::Minor),
                     2 => Ok(IntervalQuality::Major),
                     3 => Ok(IntervalQuality::Augmented),
-                    _ => Err(()),
+                    _ => Err(IntervalError::UnsupportedSemitones { number, semitones }),
                 }
             }
             3 => {
@@ -140,7 +314,7 @@ impl Interval {
                     3 => Ok(IntervalQuality::Minor),
                     4 => Ok(IntervalQuality::Major),
                     5 => Ok(IntervalQuality::Augmented),
-                    _ => Err(()),
+                    _ => Err(IntervalError::UnsupportedSemitones { number, semitones }),
                 }
             }
             4 => {
@@ -148,7 +322,7 @@ impl Interval {
                     4 => Ok(IntervalQuality::Diminished),
                     5 => Ok(IntervalQuality::Perfect),
                     6 => Ok(IntervalQuality::Augmented),
-                    _ => Err(()),
+                    _ => Err(IntervalError::UnsupportedSemitones { number, semitones }),
                 }
             }
             5 => {
@@ -156,7 +330,7 @@ impl Interval {
                     6 => Ok(IntervalQuality::Diminished),
                     7 => Ok(IntervalQuality::Perfect),
                     8 => Ok(IntervalQuality::Augmented),
-                    _ => Err(()),
+                    _ => Err(IntervalError::UnsupportedSemitones { number, semitones }),
                 }
             }
             6 => {
@@ -165,7 +339,7 @@ impl Interval {
                     8 => Ok(IntervalQuality::Minor),
                     9 => Ok(IntervalQuality::Major),
                     10 => Ok(IntervalQuality::Augmented),
-                    _ => Err(()),
+                    _ => Err(IntervalError::UnsupportedSemitones { number, semitones }),
                 }
             }
             7 => {
@@ -174,25 +348,349 @@ impl Interval {
                     10 => Ok(IntervalQuality::Minor),
                     11 => Ok(IntervalQuality::Major),
                     12 => Ok(IntervalQuality::Augmented),
-                    _ => Err(()),
+                    _ => Err(IntervalError::UnsupportedSemitones { number, semitones }),
                 }
             }
-            _ => Err(()),
+            _ => Err(IntervalError::UnsupportedNumber(number)),
         };
         return quality;
     }
 
+    /// Checks whether the interval is a perfect unison: both pitches have the same letter name, accidental and octave.
+    ///
+    /// Enharmonic pitches sound the same but are not a unison, e.g. C4 and B#3 form a diminished second.
+    pub fn is_unison(&self) -> bool {
+        self.lower.name == self.upper.name && self.lower.accidental == self.upper.accidental && self.lower.octave == self.upper.octave
+    }
+
+    /// Checks whether the interval is a perfect octave: the pitches have the same letter name and lie 12 semitones apart.
+    pub fn is_octave(&self) -> bool {
+        self.lower.name == self.upper.name && self.get_number(false) == 8 && self.get_number_of_semitones(false) == 12
+    }
+
+    /// Reduces a compound interval to its simple form by lowering the upper pitch by whole octaves,
+    /// e.g. a major tenth becomes a major third. An interval up to an octave is already simple.
+    pub fn simple(&self) -> Interval {
+        let octaves = self.get_number_of_letter_steps().saturating_sub(1) / 7;
+        let mut upper = self.upper.clone();
+        upper.octave -= octaves as i8;
+        Interval::new(self.lower.clone(), upper)
+    }
+
+    /// Inverts the interval by moving its lower pitch up an octave, e.g. C4-E4 (a major third) becomes E4-C5 (a minor sixth).
+    ///
+    /// The number becomes 9 minus the simple number, major and minor swap, augmented and diminished swap and perfect stays perfect.
+    /// A compound interval is reduced to its simple form first, see `simple`, so a major tenth also inverts to a minor sixth.
+    /// A unison inverts to an octave and an octave to a unison.
+    /// When the lower pitch is in the highest octave, the upper pitch moves down an octave instead (E126-C127 for C127-E127).
+    pub fn invert(&self) -> Interval {
+        let simple = self.simple();
+        match simple.lower.octave.checked_add(1) {
+            Some(octave) => {
+                let mut raised = simple.lower.clone();
+                raised.octave = octave;
+                Interval::new(simple.upper, raised)
+            }
+            None => {
+                // the upper pitch is in octave 127 too, so lowering it cannot overflow
+                let mut lowered = simple.upper.clone();
+                lowered.octave -= 1;
+                Interval::new(lowered, simple.lower)
+            }
+        }
+    }
+
+    /// Raises the upper pitch of a simple interval by whole octaves, keeping its quality,
+    /// e.g. a major third becomes a major tenth with one octave. This is the inverse of `simple`.
+    ///
+    /// # Arguments
+    ///
+    /// * `octaves` - The number of octaves to add
+    ///
+    /// # Returns
+    ///
+    /// The compound interval, or an error if the interval is already compound or the upper pitch would leave the octave range.
+    pub fn to_compound(&self, octaves: u8) -> Result<Interval, ()> {
+        if self.get_number(false) > 8 {
+            return Err(());
+        }
+        let octave = i8::try_from(octaves).ok()
+            .and_then(|octaves| self.upper.octave.checked_add(octaves))
+            .ok_or(())?;
+        let upper = Pitch::new(self.upper.name.clone(), octave, self.upper.accidental.clone());
+        Ok(Interval::new(self.lower.clone(), upper))
+    }
+
     /// Calculates the specific interval.
     ///
     /// # Returns
     ///
-    ///  A tuple containing the interval number, the interval quality and a boolean indicating if the interval is greater than an octave.
-    pub fn get_specific_interval(&self) -> (u8, IntervalQuality, bool) {
-        (
+    ///  A tuple containing the interval number, the interval quality and a boolean indicating if the interval is greater than an octave,
+    ///  or an error if the quality cannot be named, see `get_quality`.
+    pub fn get_specific_interval(&self) -> Result<(u8, IntervalQuality, bool), IntervalError> {
+        Ok((
             self.get_number(true),
-            self.get_quality().unwrap(),
+            self.get_quality()?,
             self.get_number_of_semitones(false) > 12,
-        )
+        ))
+    }
+
+    /// Classifies the interval as a perfect consonance, an imperfect consonance or a dissonance, see `Consonance`.
+    ///
+    /// The classification uses the simple interval, see `get_specific_interval`, so a compound interval sounds like its
+    /// simple form, e.g. a major tenth is an imperfect consonance. The perfect fourth counts as a dissonance, as it does
+    /// above the bass in two-part counterpoint. An interval without a named quality is a dissonance.
+    pub fn consonance(&self) -> Consonance {
+        match self.get_specific_interval() {
+            Ok((1 | 5, IntervalQuality::Perfect, _)) => Consonance::PerfectConsonance,
+            Ok((3 | 6, IntervalQuality::Major | IntervalQuality::Minor, _)) => Consonance::ImperfectConsonance,
+            _ => Consonance::Dissonance,
+        }
+    }
+
+    /// Checks whether the interval is a perfect or an imperfect consonance, see `consonance`.
+    pub fn is_consonant(&self) -> bool {
+        self.consonance() != Consonance::Dissonance
+    }
+}
+
+impl Display for Interval {
+    /// Writes the interval in shorthand: the quality (P, M, m, A or d) followed by the number, e.g. "P5", "m3" or "A4".
+    ///
+    /// A compound interval keeps its full number, e.g. "M10" for C4-E5, so the size can be read without a separate marker.
+    /// An interval whose quality has no name, e.g. a doubly augmented one, is written with "?" as its quality.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let quality = match self.get_quality() {
+            Ok(IntervalQuality::Perfect) => "P",
+            Ok(IntervalQuality::Major) => "M",
+            Ok(IntervalQuality::Minor) => "m",
+            Ok(IntervalQuality::Augmented) => "A",
+            Ok(IntervalQuality::Diminished) => "d",
+            Err(_) => "?",
+        };
+        write!(f, "{}{}", quality, self.get_number(false))
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod interval_serde_tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let interval = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::G, 4));
+        let json = serde_json::to_string(&interval).unwrap();
+        assert_eq!(json, r#"{"lower":"C4","upper":"G4"}"#);
+        let parsed: Interval = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.get_number(false), 5);
+        assert_eq!(parsed.get_quality(), Ok(IntervalQuality::Perfect));
+        let quality: IntervalQuality = serde_json::from_str(&serde_json::to_string(&IntervalQuality::Minor).unwrap()).unwrap();
+        assert_eq!(quality, IntervalQuality::Minor);
+        let step: IntervalStep = serde_json::from_str(&serde_json::to_string(&IntervalStep::Half).unwrap()).unwrap();
+        assert_eq!(step, IntervalStep::Half);
+    }
+
+    #[test]
+    fn test_reversed_pitches() {
+        let parsed: Interval = serde_json::from_str(r#"{"lower":"G4","upper":"C4"}"#).unwrap();
+        assert_eq!(parsed.lower().to_string(), "C4");
+        assert_eq!(parsed.get_number_of_semitones(false), 7);
+        assert_eq!(parsed.get_quality(), Ok(IntervalQuality::Perfect));
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), r#"{"lower":"C4","upper":"G4"}"#);
+    }
+}
+
+#[cfg(test)]
+mod display_tests {
+    use super::*;
+
+    fn shorthand(lower: &str, upper: &str) -> String {
+        Interval::new(Pitch::try_from(lower).unwrap(), Pitch::try_from(upper).unwrap()).to_string()
+    }
+
+    #[test]
+    fn test_qualities() {
+        assert_eq!(shorthand("C4", "G4"), "P5");
+        assert_eq!(shorthand("C4", "E4"), "M3");
+        assert_eq!(shorthand("C4", "Eb4"), "m3");
+        assert_eq!(shorthand("C4", "F#4"), "A4");
+        assert_eq!(shorthand("C4", "Gb4"), "d5");
+        assert_eq!(shorthand("C4", "C4"), "P1");
+        assert_eq!(shorthand("C4", "C5"), "P8");
+    }
+
+    #[test]
+    fn test_compound() {
+        assert_eq!(shorthand("C4", "E5"), "M10");
+        assert_eq!(shorthand("C4", "G5"), "P12");
+    }
+}
+
+#[cfg(test)]
+mod consonance_tests {
+    use super::*;
+
+    fn interval(lower: &str, upper: &str) -> Interval {
+        Interval::new(Pitch::try_from(lower).unwrap(), Pitch::try_from(upper).unwrap())
+    }
+
+    #[test]
+    fn test_perfect_consonances() {
+        assert_eq!(interval("C4", "G4").consonance(), Consonance::PerfectConsonance);
+        assert_eq!(interval("C4", "C5").consonance(), Consonance::PerfectConsonance);
+        assert_eq!(interval("C4", "C4").consonance(), Consonance::PerfectConsonance);
+        assert!(interval("C4", "G4").is_consonant());
+    }
+
+    #[test]
+    fn test_imperfect_consonances() {
+        assert_eq!(interval("C4", "E4").consonance(), Consonance::ImperfectConsonance);
+        assert_eq!(interval("E4", "C5").consonance(), Consonance::ImperfectConsonance);
+        assert_eq!(interval("C4", "E5").consonance(), Consonance::ImperfectConsonance);
+        assert!(interval("C4", "E4").is_consonant());
+    }
+
+    #[test]
+    fn test_dissonances() {
+        assert_eq!(interval("C4", "Bb4").consonance(), Consonance::Dissonance);
+        assert_eq!(interval("C4", "F#4").consonance(), Consonance::Dissonance);
+        assert_eq!(interval("C4", "D4").consonance(), Consonance::Dissonance);
+        assert_eq!(interval("C4", "F4").consonance(), Consonance::Dissonance);
+        assert!(!interval("C4", "Bb4").is_consonant());
+        assert!(!interval("C4", "F#4").is_consonant());
+    }
+}
+
+#[cfg(test)]
+mod invert_tests {
+    use super::*;
+
+    fn invert(lower: &str, upper: &str) -> Interval {
+        Interval::new(Pitch::try_from(lower).unwrap(), Pitch::try_from(upper).unwrap()).invert()
+    }
+
+    #[test]
+    fn test_invert() {
+        assert_eq!(invert("C4", "E4").get_specific_interval(), Ok((6, IntervalQuality::Minor, false)));
+        assert_eq!(invert("C4", "G4").get_specific_interval(), Ok((4, IntervalQuality::Perfect, false)));
+        assert_eq!(invert("C4", "F#4").get_specific_interval(), Ok((5, IntervalQuality::Diminished, false)));
+        assert_eq!(invert("C4", "Gb4").get_specific_interval(), Ok((4, IntervalQuality::Augmented, false)));
+        assert_eq!(invert("D4", "C5").get_specific_interval(), Ok((2, IntervalQuality::Major, false)));
+        let interval = invert("C4", "E4");
+        assert_eq!((interval.lower().to_string(), interval.upper().to_string()), ("E4".to_string(), "C5".to_string()));
+    }
+
+    #[test]
+    fn test_unison_and_octave() {
+        assert!(invert("C4", "C4").is_octave());
+        assert!(invert("C4", "C5").is_unison());
+        // a compound interval inverts like its simple form
+        assert_eq!(invert("C4", "E5").get_specific_interval(), Ok((6, IntervalQuality::Minor, false)));
+    }
+
+    #[test]
+    fn test_highest_octave() {
+        let c127 = Pitch::new_without_accidental(PitchName::C, 127);
+        let interval = Interval::new(c127.clone(), Pitch::new_without_accidental(PitchName::E, 127));
+        let inverted = interval.invert();
+        assert_eq!((inverted.lower().to_string(), inverted.upper().to_string()), ("E126".to_string(), "C127".to_string()));
+        assert_eq!(inverted.get_specific_interval(), Ok((6, IntervalQuality::Minor, false)));
+        assert!(Interval::new(c127.clone(), c127.clone()).invert().is_octave());
+        assert!(Interval::new(Pitch::new_without_accidental(PitchName::C, 126), c127).invert().is_unison());
+    }
+}
+
+#[cfg(test)]
+mod from_parts_tests {
+    use super::*;
+
+    #[test]
+    fn test_qualities() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let cases = [
+            (3, IntervalQuality::Major, "E4"),
+            (3, IntervalQuality::Minor, "Eb4"),
+            (5, IntervalQuality::Perfect, "G4"),
+            (4, IntervalQuality::Augmented, "F#4"),
+            (5, IntervalQuality::Diminished, "Gb4"),
+            (7, IntervalQuality::Diminished, "Bbb4"),
+            (8, IntervalQuality::Perfect, "C5"),
+        ];
+        for (number, quality, upper) in cases {
+            let interval = Interval::from_parts(number, quality.clone(), c4.clone()).unwrap();
+            assert_eq!(interval.lower().to_string(), "C4");
+            assert_eq!(interval.upper().to_string(), upper);
+            assert_eq!(interval.get_number(false), number);
+            assert_eq!(interval.get_quality(), Ok(quality));
+        }
+    }
+
+    #[test]
+    fn test_compound() {
+        let interval = Interval::from_parts(10, IntervalQuality::Major, Pitch::new_without_accidental(PitchName::C, 4)).unwrap();
+        assert_eq!(interval.upper().to_string(), "E5");
+        assert_eq!(interval.get_specific_interval(), Ok((3, IntervalQuality::Major, true)));
+    }
+
+    #[test]
+    fn test_impossible() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert!(Interval::from_parts(3, IntervalQuality::Perfect, c4.clone()).is_err());
+        assert!(Interval::from_parts(5, IntervalQuality::Major, c4.clone()).is_err());
+        assert!(Interval::from_parts(0, IntervalQuality::Perfect, c4).is_err());
+    }
+
+    #[test]
+    fn test_root_stays_lower() {
+        let interval = Interval::from_parts(2, IntervalQuality::Diminished, Pitch::new_without_accidental(PitchName::C, 4)).unwrap();
+        assert_eq!(interval.lower().to_string(), "C4");
+        assert_eq!(interval.upper().to_string(), "Dbb4");
+        assert_eq!(interval.get_specific_interval(), Ok((2, IntervalQuality::Diminished, false)));
+    }
+
+    #[test]
+    fn test_from_name() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let fifth = Interval::from_name(PERFECT_FIFTH, c4.clone()).unwrap();
+        assert_eq!(fifth.upper().to_string(), "G4");
+        assert_eq!(fifth.to_string(), PERFECT_FIFTH);
+        assert_eq!(Interval::from_name(TRITONE, c4.clone()).unwrap().upper().to_string(), "F#4");
+        assert_eq!(Interval::from_name(MINOR_THIRD, c4.clone()).unwrap().upper().to_string(), "Eb4");
+        assert_eq!(Interval::from_name("M10", c4.clone()).unwrap().upper().to_string(), "E5");
+        assert!(Interval::from_name("P3", c4.clone()).is_err());
+        assert!(Interval::from_name("X5", c4.clone()).is_err());
+        assert!(Interval::from_name("P", c4).is_err());
+    }
+}
+
+#[cfg(test)]
+mod semitones_of_tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_intervals() {
+        assert_eq!(semitones_of(1, &IntervalQuality::Perfect), Ok(0));
+        assert_eq!(semitones_of(2, &IntervalQuality::Minor), Ok(1));
+        assert_eq!(semitones_of(3, &IntervalQuality::Major), Ok(4));
+        assert_eq!(semitones_of(4, &IntervalQuality::Augmented), Ok(6));
+        assert_eq!(semitones_of(5, &IntervalQuality::Diminished), Ok(6));
+        assert_eq!(semitones_of(7, &IntervalQuality::Diminished), Ok(9));
+        assert_eq!(semitones_of(8, &IntervalQuality::Perfect), Ok(12));
+    }
+
+    #[test]
+    fn test_compound_intervals() {
+        assert_eq!(semitones_of(9, &IntervalQuality::Major), Ok(14));
+        assert_eq!(semitones_of(12, &IntervalQuality::Perfect), Ok(19));
+        assert_eq!(semitones_of(15, &IntervalQuality::Diminished), Ok(23));
+    }
+
+    #[test]
+    fn test_impossible_intervals() {
+        assert_eq!(semitones_of(0, &IntervalQuality::Perfect), Err(()));
+        assert_eq!(semitones_of(1, &IntervalQuality::Diminished), Err(()));
+        assert_eq!(semitones_of(3, &IntervalQuality::Perfect), Err(()));
+        assert_eq!(semitones_of(5, &IntervalQuality::Major), Err(()));
     }
 }
 
@@ -253,10 +751,25 @@ mod get_number_tests {
         let interval = Interval::new(p1, p2);
         assert_eq!(interval.get_number(true), 4);
     }
+
+    #[test]
+    fn test_upper_letter_before_lower_letter() {
+        let p1 = Pitch::new_without_accidental(PitchName::B, 0);
+        let p2 = Pitch::new_without_accidental(PitchName::C, 1);
+        let interval = Interval::new(p1, p2);
+        assert_eq!(interval.get_number(false), 2);
+
+        let p1 = Pitch::new_without_accidental(PitchName::B, 0);
+        let p2 = Pitch::new_without_accidental(PitchName::C, 2);
+        let interval = Interval::new(p1, p2);
+        assert_eq!(interval.get_number(false), 9);
+        assert_eq!(interval.get_number(true), 2);
+    }
 }
 
 #[cfg(test)]
 mod get_number_of_semitones_tests {
+    use crate::theory::pitch::Accidental;
     use super::*;
 
     #[test]
@@ -307,6 +820,29 @@ mod get_number_of_semitones_tests {
         let interval = Interval::new(p1, p2);
         assert_eq!(interval.get_number_of_semitones(true), 5);
     }
+
+    #[test]
+    fn test_ignore_octave_over_two_octaves() {
+        let p1 = Pitch::new_without_accidental(PitchName::C, 0);
+        let p2 = Pitch::new_without_accidental(PitchName::E, 3);
+        let interval = Interval::new(p1, p2);
+        assert_eq!(interval.get_number_of_semitones(true), 4);
+
+        let p1 = Pitch::new_without_accidental(PitchName::C, 0);
+        let p2 = Pitch::new_without_accidental(PitchName::C, 3);
+        let interval = Interval::new(p1, p2);
+        assert_eq!(interval.get_number_of_semitones(true), 0);
+
+        let p1 = Pitch::new_without_accidental(PitchName::C, 0);
+        let p2 = Pitch::new(PitchName::C, 4, Accidental::Flat);
+        let interval = Interval::new(p1, p2);
+        assert_eq!(interval.get_number_of_semitones(true), 11);
+
+        let p1 = Pitch::new_without_accidental(PitchName::C, 0);
+        let p2 = Pitch::new(PitchName::B, 3, Accidental::Sharp);
+        let interval = Interval::new(p1, p2);
+        assert_eq!(interval.get_number_of_semitones(true), 12);
+    }
 }
 
 #[cfg(test)]
@@ -423,10 +959,125 @@ mod get_quality_tests {
         let interval = Interval::new(p1, p2);
         assert_eq!(interval.get_quality(), Ok(IntervalQuality::Augmented));
     }
+
+    #[test]
+    fn test_compound_intervals() {
+        // major ninth and major second
+        let ninth = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::D, 5));
+        let second = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::D, 4));
+        assert_eq!(ninth.get_quality(), Ok(IntervalQuality::Major));
+        assert_eq!(ninth.get_quality(), second.get_quality());
+
+        // perfect twelfth and perfect fifth
+        let twelfth = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::G, 5));
+        let fifth = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::G, 4));
+        assert_eq!(twelfth.get_quality(), Ok(IntervalQuality::Perfect));
+        assert_eq!(twelfth.get_quality(), fifth.get_quality());
+
+        // major tenth and major third
+        let tenth = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::E, 5));
+        let third = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::E, 4));
+        assert_eq!(tenth.get_quality(), Ok(IntervalQuality::Major));
+        assert_eq!(tenth.get_quality(), third.get_quality());
+
+        // minor ninth where the upper letter comes before the lower one
+        let ninth = Interval::new(Pitch::new_without_accidental(PitchName::B, 3), Pitch::new_without_accidental(PitchName::C, 5));
+        assert_eq!(ninth.get_quality(), Ok(IntervalQuality::Minor));
+    }
+
+    #[test]
+    fn test_octave() {
+        let p1 = Pitch::new_without_accidental(PitchName::C, 4);
+        let p2 = Pitch::new_without_accidental(PitchName::C, 5);
+        let interval = Interval::new(p1, p2);
+        assert_eq!(interval.get_quality(), Ok(IntervalQuality::Perfect));
+    }
+
+    #[test]
+    fn test_spelling_differs_from_sound() {
+        // sounds like a major third
+        let interval = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new(PitchName::F, 4, Accidental::Flat));
+        assert_eq!(interval.get_number(false), 4);
+        assert_eq!(interval.get_quality(), Ok(IntervalQuality::Diminished));
+        // sounds like a perfect fourth
+        let interval = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new(PitchName::E, 4, Accidental::Sharp));
+        assert_eq!(interval.get_number(false), 3);
+        assert_eq!(interval.get_quality(), Ok(IntervalQuality::Augmented));
+        // sounds like a unison
+        let interval = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new(PitchName::D, 4, Accidental::DoubleFlat));
+        assert_eq!(interval.get_number(false), 2);
+        assert_eq!(interval.get_quality(), Ok(IntervalQuality::Diminished));
+        // sounds like a major seventh
+        let interval = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new(PitchName::C, 5, Accidental::Flat));
+        assert_eq!(interval.get_number(false), 8);
+        assert_eq!(interval.get_quality(), Ok(IntervalQuality::Diminished));
+    }
+}
+
+#[cfg(test)]
+mod unison_and_octave_tests {
+    use crate::theory::pitch::Accidental;
+    use super::*;
+
+    #[test]
+    fn test_unison() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert!(Interval::new(c4.clone(), c4.clone()).is_unison());
+        assert!(!Interval::new(c4.clone(), c4.clone()).is_octave());
+        assert!(!Interval::new(c4, Pitch::new(PitchName::B, 3, Accidental::Sharp)).is_unison());
+    }
+
+    #[test]
+    fn test_octave() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let c5 = Pitch::new_without_accidental(PitchName::C, 5);
+        assert!(Interval::new(c4.clone(), c5.clone()).is_octave());
+        assert!(!Interval::new(c4.clone(), c5).is_unison());
+        assert!(!Interval::new(c4.clone(), Pitch::new(PitchName::C, 5, Accidental::Flat)).is_octave());
+        assert!(!Interval::new(c4, Pitch::new_without_accidental(PitchName::C, 6)).is_octave());
+    }
+
+    #[test]
+    fn test_neither() {
+        let interval = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new(PitchName::C, 4, Accidental::Sharp));
+        assert!(!interval.is_unison());
+        assert!(!interval.is_octave());
+    }
+}
+
+#[cfg(test)]
+mod compound_tests {
+    use super::*;
+
+    #[test]
+    fn test_to_compound() {
+        let third = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::E, 4));
+        let tenth = third.to_compound(1).unwrap();
+        assert_eq!(tenth.get_number(false), 10);
+        assert_eq!(tenth.get_quality(), Ok(IntervalQuality::Major));
+        assert_eq!(third.to_compound(2).unwrap().get_number(false), 17);
+        assert_eq!(third.to_compound(0).unwrap().get_number(false), 3);
+        assert!(tenth.to_compound(1).is_err());
+        assert!(third.to_compound(200).is_err());
+    }
+
+    #[test]
+    fn test_simple() {
+        let tenth = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::E, 6));
+        let third = tenth.simple();
+        assert_eq!(third.get_number(false), 3);
+        assert_eq!(third.get_quality(), Ok(IntervalQuality::Major));
+
+        let octave = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::C, 5));
+        assert_eq!(octave.simple().get_number(false), 8);
+        let sixth = Interval::new(Pitch::new_without_accidental(PitchName::E, 4), Pitch::new_without_accidental(PitchName::C, 5));
+        assert_eq!(sixth.to_compound(1).unwrap().simple().get_number(false), 6);
+    }
 }
 
 #[cfg(test)]
 mod get_specific_interval_tests {
+    use crate::theory::pitch::Accidental;
     use super::*;
 
     #[test]
@@ -434,12 +1085,12 @@ mod get_specific_interval_tests {
         let p1 = Pitch::new_without_accidental(PitchName::C, 0);
         let p2 = Pitch::new_without_accidental(PitchName::E, 0);
         let interval = Interval::new(p1, p2);
-        assert_eq!(interval.get_specific_interval(), (3, IntervalQuality::Major, false));
+        assert_eq!(interval.get_specific_interval(), Ok((3, IntervalQuality::Major, false)));
 
         let p1 = Pitch::new_without_accidental(PitchName::C, 0);
         let p2 = Pitch::new_without_accidental(PitchName::G, 0);
         let interval = Interval::new(p1, p2);
-        assert_eq!(interval.get_specific_interval(), (5, IntervalQuality::Perfect, false));
+        assert_eq!(interval.get_specific_interval(), Ok((5, IntervalQuality::Perfect, false)));
     }
 
     #[test]
@@ -447,11 +1098,21 @@ mod get_specific_interval_tests {
         let p1 = Pitch::new_without_accidental(PitchName::C, 0);
         let p2 = Pitch::new_without_accidental(PitchName::E, 1);
         let interval = Interval::new(p1, p2);
-        assert_eq!(interval.get_specific_interval(), (3, IntervalQuality::Major, true));
+        assert_eq!(interval.get_specific_interval(), Ok((3, IntervalQuality::Major, true)));
 
         let p1 = Pitch::new_without_accidental(PitchName::C, 1);
         let p2 = Pitch::new_without_accidental(PitchName::G, 3);
         let interval = Interval::new(p1, p2);
-        assert_eq!(interval.get_specific_interval(), (5, IntervalQuality::Perfect, true));
+        assert_eq!(interval.get_specific_interval(), Ok((5, IntervalQuality::Perfect, true)));
+    }
+
+    #[test]
+    fn test_doubly_augmented_is_an_error() {
+        let p1 = Pitch::new_without_accidental(PitchName::C, 4);
+        let p2 = Pitch::new(PitchName::F, 4, Accidental::DoubleSharp);
+        let interval = Interval::new(p1, p2);
+        let error = interval.get_specific_interval().unwrap_err();
+        assert_eq!(error, IntervalError::UnsupportedSemitones { number: 4, semitones: 7 });
+        assert_eq!(error.to_string(), "No interval quality for a 4 of 7 semitones");
     }
-}
\ No newline at end of file
+}
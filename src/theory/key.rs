@@ -0,0 +1,698 @@
+use crate::theory::chord::{Chord, ChordQuality};
+use crate::theory::interval::{Interval, IntervalStep};
+use crate::theory::pitch::{Accidental, Pitch, PitchName};
+use crate::theory::pitch_class::PitchClass;
+use crate::theory::scale::Scale;
+use crate::utils::float_mod;
+
+/// The three forms of the minor mode.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MinorForm {
+    #[default]
+    Natural,
+    Harmonic,
+    Melodic,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    Major,
+    Minor(MinorForm),
+}
+
+/// The role of a pitch within a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PitchFunction {
+    /// A tone of the tonic triad, with its scale degree (1, 3 or 5)
+    TonicChordTone(usize),
+    /// A pitch of the scale outside the tonic triad, with its scale degree
+    Diatonic(usize),
+    /// A pitch outside the scale
+    Chromatic,
+}
+
+/// The kinds of cadence, by the chords that end a phrase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CadenceKind {
+    /// V7 to I
+    Authentic,
+    /// IV to I
+    Plagal,
+    /// I to V
+    Half,
+    /// V7 to vi
+    Deceptive,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Key {
+    pub tonic: Pitch,
+    pub mode: Mode,
+}
+
+impl Key {
+    pub fn new(tonic: Pitch, mode: Mode) -> Self {
+        Self { tonic, mode }
+    }
+
+    pub fn major(tonic: Pitch) -> Self {
+        Self::new(tonic, Mode::Major)
+    }
+
+    /// Creates a minor key in its natural form.
+    pub fn minor(tonic: Pitch) -> Self {
+        Self::new(tonic, Mode::Minor(MinorForm::default()))
+    }
+
+    /// Gets the scale of the key.
+    ///
+    /// # Returns
+    ///
+    /// The major scale for a major key, or the natural, harmonic or (ascending) melodic minor scale for a minor key.
+    pub fn scale(&self) -> Scale {
+        match &self.mode {
+            Mode::Major => Scale::major(),
+            Mode::Minor(MinorForm::Natural) => Scale::natural_minor(),
+            Mode::Minor(MinorForm::Harmonic) => Scale::harmonic_minor(),
+            Mode::Minor(MinorForm::Melodic) => Scale::melodic_minor(),
+        }
+    }
+
+    /// Gets the number of accidentals in the key signature.
+    ///
+    /// # Returns
+    ///
+    /// The number of sharps as a positive number, or the number of flats as a negative number,
+    /// i.e. the position of the key on the circle of fifths (G major is 1, F major and D minor are -1).
+    pub fn accidental_count(&self) -> i8 {
+        // fifths above C of the natural letters, from C to B
+        let letter = [0, 2, 4, -1, 1, 3, 5][self.tonic.name.position() as usize];
+        let alteration = match self.tonic.accidental {
+            Accidental::TripleFlat => -3,
+            Accidental::DoubleFlat => -2,
+            Accidental::Flat => -1,
+            Accidental::None => 0,
+            Accidental::Sharp => 1,
+            Accidental::DoubleSharp => 2,
+            Accidental::TripleSharp => 3,
+        };
+        let major = letter + alteration * 7;
+        match self.mode {
+            Mode::Major => major,
+            // a minor key has the signature of the major key a minor third above it
+            Mode::Minor(_) => major - 3,
+        }
+    }
+
+    /// Gets the relative key, which shares the key signature: the relative minor a minor third below
+    /// the tonic of a major key, or the relative major a minor third above the tonic of a minor key.
+    ///
+    /// Fails if the new tonic would need more than a double sharp or double flat.
+    pub fn relative(&self) -> Result<Key, ()> {
+        match self.mode {
+            Mode::Major => Ok(Key::minor(self.tonic.spell_at(-2, -3)?)),
+            Mode::Minor(_) => Ok(Key::major(self.tonic.spell_at(2, 3)?)),
+        }
+    }
+
+    /// Gets the parallel key, which shares the tonic, in the other mode (C major and C minor).
+    pub fn parallel(&self) -> Key {
+        match self.mode {
+            Mode::Major => Key::minor(self.tonic.clone()),
+            Mode::Minor(_) => Key::major(self.tonic.clone()),
+        }
+    }
+
+    /// Checks whether the other key is closely related to this key, i.e. a common target for a modulation.
+    ///
+    /// Closely related keys differ by at most one accidental in their key signatures (which includes the relative key),
+    /// or are the parallel key. The form of a minor key is ignored.
+    pub fn is_closely_related(&self, other: &Key) -> bool {
+        if (self.accidental_count() - other.accidental_count()).abs() <= 1 {
+            return true;
+        }
+        let parallel = self.parallel();
+        PitchClass::from(parallel.tonic) == PitchClass::from(other.tonic.clone())
+            && matches!(parallel.mode, Mode::Major) == matches!(other.mode, Mode::Major)
+    }
+
+    /// Gets the seventh degree of the key, above the tonic.
+    ///
+    /// This is the leading tone a half step below the tonic in major and in harmonic or melodic minor,
+    /// or the subtonic a whole step below the tonic in natural minor (B in C major and C harmonic minor, Bb in C natural minor).
+    pub fn leading_tone(&self) -> Pitch {
+        let scale = self.scale();
+        scale.degree(&self.tonic, 7).unwrap_or_else(|_| {
+            // the seventh cannot be spelled on this tonic, fall back to a sharp spelling
+            self.tonic.transpose_semitones(scale.degree_offsets()[6] as i32)
+        })
+    }
+
+    /// Classifies a pitch in any octave as a tone of the tonic triad, another diatonic pitch or a chromatic pitch,
+    /// see `degree_of`.
+    pub fn classify_pitch(&self, pitch: &Pitch) -> PitchFunction {
+        match self.degree_of(pitch) {
+            Some(degree @ (1 | 3 | 5)) => PitchFunction::TonicChordTone(degree),
+            Some(degree) => PitchFunction::Diatonic(degree),
+            None => PitchFunction::Chromatic,
+        }
+    }
+
+    /// Builds a triad on every degree of the scale of the key, stacking the scale's own thirds (C, Dm, Em, F, G, Am, Bdim in C major).
+    ///
+    /// Fails if a triad is not major, minor, augmented or diminished, or a chord tone cannot be spelled.
+    pub fn diatonic_triads(&self) -> Result<Vec<Chord>, ()> {
+        let scale = self.scale();
+        let offsets = scale.degree_offsets();
+        let mut triads = Vec::with_capacity(offsets.len());
+        for degree in 1..=offsets.len() {
+            let root = scale.degree(&self.tonic, degree)?;
+            let semitones = |other: usize| -> Result<u16, ()> {
+                Ok(Interval::new(root.clone(), scale.degree(&self.tonic, other)?).get_number_of_semitones(false))
+            };
+            let quality = match (semitones(degree + 2)?, semitones(degree + 4)?) {
+                (4, 7) => ChordQuality::Major,
+                (3, 7) => ChordQuality::Minor,
+                (4, 8) => ChordQuality::Augmented,
+                (3, 6) => ChordQuality::Diminished,
+                _ => return Err(()),
+            };
+            triads.push(Chord::try_new(root, quality)?);
+        }
+        Ok(triads)
+    }
+
+    /// Gets the dominant seventh chord (V7) of the key, built on the fifth degree above the tonic (G7 in C major).
+    ///
+    /// A minor key uses the same chord as its parallel major, so the third of the chord is the raised leading tone.
+    ///
+    /// Fails if a chord tone cannot be spelled with at most a double sharp or double flat.
+    pub fn dominant_seventh(&self) -> Result<Chord, ()> {
+        Chord::try_new(self.tonic.spell_at(4, 7)?, ChordQuality::DominantSeventh)
+    }
+
+    /// Builds a seventh chord on a degree of the scale of the key, stacking the scale's own thirds (Dm7 on the second degree of C major).
+    ///
+    /// Fails if the chord is not one of the seventh chord qualities, or a chord tone cannot be spelled.
+    fn diatonic_seventh(&self, degree: usize) -> Result<Chord, ()> {
+        let scale = self.scale();
+        let root = scale.degree(&self.tonic, degree)?;
+        let semitones = |other: usize| -> Result<u16, ()> {
+            Ok(Interval::new(root.clone(), scale.degree(&self.tonic, other)?).get_number_of_semitones(false))
+        };
+        let quality = match (semitones(degree + 2)?, semitones(degree + 4)?, semitones(degree + 6)?) {
+            (4, 7, 10) => ChordQuality::DominantSeventh,
+            (4, 7, 11) => ChordQuality::MajorSeventh,
+            (3, 7, 10) => ChordQuality::MinorSeventh,
+            (3, 6, 10) => ChordQuality::HalfDiminishedSeventh,
+            (3, 6, 9) => ChordQuality::DiminishedSeventh,
+            _ => return Err(()),
+        };
+        Chord::try_new(root, quality)
+    }
+
+    /// Gets the scale degree of a pitch in any octave.
+    ///
+    /// # Returns
+    ///
+    /// The degree, starting with 1 for the tonic, or `None` if the pitch is not diatonic to the key.
+    pub fn degree_of(&self, pitch: &Pitch) -> Option<usize> {
+        let distance = float_mod(f32::from(pitch.clone()) - f32::from(self.tonic.clone()), 6.0) * 2.0;
+        self.scale()
+            .degree_offsets()
+            .iter()
+            .position(|offset| *offset as f32 == distance)
+            .map(|index| index + 1)
+    }
+}
+
+/// Spells a passage of pitches for notation in the context of a key, keeping the sound of every pitch.
+///
+/// Pitches diatonic to the key are spelled as their scale degree (Gb becomes F# in D major).
+/// Chromatic pitches use as few accidentals as possible, and sharps in keys with sharps or none, flats in keys with flats,
+/// so that the spelling does not flip between sharps and flats within the passage.
+///
+/// # Arguments
+///
+/// * `pitches` - The pitches of the passage
+/// * `key` - The key of the passage
+pub fn spell_for_notation(pitches: &[Pitch], key: &Key) -> Vec<Pitch> {
+    let prefer_flats = key.accidental_count() < 0;
+    pitches.iter().map(|pitch| {
+        let spellings: Vec<Pitch> = (-2..=2).filter_map(|letter_steps| pitch.spell_at(letter_steps, 0).ok()).collect();
+        if let Some(degree) = key.degree_of(pitch) {
+            if let Ok(diatonic) = key.scale().degree(&key.tonic, degree) {
+                if let Some(spelling) = spellings.iter().find(|spelling| spelling.name == diatonic.name) {
+                    return spelling.clone();
+                }
+            }
+        }
+        spellings.into_iter()
+            .min_by_key(|spelling| {
+                let is_flat = matches!(spelling.accidental, Accidental::Flat | Accidental::DoubleFlat | Accidental::TripleFlat);
+                let against_key = spelling.accidental_complexity() > 0 && is_flat != prefer_flats;
+                (spelling.accidental_complexity(), against_key)
+            })
+            .unwrap_or_else(|| pitch.clone())
+    }).collect()
+}
+
+/// Moves a pitch to the nearest pitch of the scale of the key, spelled as its scale degree, see `spell_for_notation`.
+///
+/// A pitch of the scale is only respelled. A chromatic pitch halfway between two scale degrees snaps to the degree
+/// on its own letter name, so C# snaps to C and Db to D in C major.
+pub fn snap_to_scale(pitch: &Pitch, key: &Key) -> Pitch {
+    let value = f32::from(pitch.clone());
+    for semitones in 0..=6 {
+        let distance = semitones as f32 * f32::from(IntervalStep::Half);
+        let candidates: Vec<Pitch> = [value - distance, value + distance]
+            .into_iter()
+            .filter_map(|value| Pitch::try_from(value).ok())
+            .filter(|candidate| key.degree_of(candidate).is_some())
+            .collect();
+        let snapped = spell_for_notation(&candidates, key);
+        if let Some(snapped) = snapped.iter().find(|snapped| snapped.name == pitch.name).or(snapped.first()) {
+            return snapped.clone();
+        }
+    }
+    pitch.clone()
+}
+
+/// Moves every pitch of a melody to the nearest pitch of the scale of the key, see `snap_to_scale`.
+///
+/// # Returns
+///
+/// The melody with only pitches diatonic to the key.
+pub fn snap_melody_to_scale(pitches: &[Pitch], key: &Key) -> Vec<Pitch> {
+    pitches.iter().map(|pitch| snap_to_scale(pitch, key)).collect()
+}
+
+/// Lists the tonics of the twelve major keys in octave 4 along the circle of fifths, starting from C.
+///
+/// The keys with up to six sharps come first, followed by the keys with five flats down to one (C, G, ..., F#, Db, ..., F).
+pub fn circle_of_fifths() -> Vec<Pitch> {
+    let mut tonic = Pitch::new_without_accidental(PitchName::C, 4);
+    let mut tonics = Vec::with_capacity(12);
+    for _ in 0..12 {
+        tonics.push(tonic.clone());
+        // a fifth up, or a fourth down to stay in octave 4
+        tonic = tonic.spell_at(4, 7).unwrap();
+        if tonic.octave > 4 {
+            tonic = tonic.spell_at(-7, -12).unwrap();
+        }
+        if Key::major(tonic.clone()).accidental_count() > 6 {
+            tonic = tonic.spell_at(1, 0).unwrap();
+        }
+    }
+    tonics
+}
+
+/// Lists the twelve major keys along the circle of fifths, see `circle_of_fifths`.
+///
+/// # Returns
+///
+/// The tonic and the pitches of the scale of each key, spelled in the key (Db major uses flats).
+pub fn all_major_scales() -> Vec<(Pitch, Vec<Pitch>)> {
+    all_scales(circle_of_fifths().into_iter().map(Key::major).collect())
+}
+
+/// Lists the twelve natural minor keys along the circle of fifths, starting from A, see `circle_of_fifths`.
+///
+/// Like the major tonics, the minor tonics are in octave 4 (A4, E4, B4, ...).
+///
+/// # Returns
+///
+/// The tonic and the pitches of the scale of each key, spelled in the key.
+pub fn all_minor_scales() -> Vec<(Pitch, Vec<Pitch>)> {
+    let tonics = circle_of_fifths().into_iter().map(|tonic| {
+        let mut tonic = tonic.relative_minor();
+        tonic.octave = 4;
+        tonic
+    });
+    all_scales(tonics.map(Key::minor).collect())
+}
+
+fn all_scales(keys: Vec<Key>) -> Vec<(Pitch, Vec<Pitch>)> {
+    keys.into_iter()
+        .map(|key| {
+            let pitches = key.scale().pitches(&key.tonic).unwrap();
+            (key.tonic, pitches)
+        })
+        .collect()
+}
+
+/// Gets the two chords of a cadence in a key.
+///
+/// The dominant is always major, so in a minor key it contains the raised leading tone, see `Key::dominant_seventh`.
+/// The other chords are the diatonic triads of the key, see `Key::diatonic_triads`.
+///
+/// # Returns
+///
+/// The chord before and the chord of the cadence, or an error if a chord cannot be built in the key.
+pub fn cadence(key: &Key, kind: CadenceKind) -> Result<Vec<Chord>, ()> {
+    let triads = key.diatonic_triads()?;
+    let triad = |degree: usize| triads.get(degree - 1).cloned().ok_or(());
+    let chords = match kind {
+        CadenceKind::Authentic => vec![key.dominant_seventh()?, triad(1)?],
+        CadenceKind::Plagal => vec![triad(4)?, triad(1)?],
+        CadenceKind::Half => vec![triad(1)?, Chord::try_new(key.tonic.spell_at(4, 7)?, ChordQuality::Major)?],
+        CadenceKind::Deceptive => vec![key.dominant_seventh()?, triad(6)?],
+    };
+    Ok(chords)
+}
+
+/// Gets the ii-V-I progression of a key, the seventh chords on the second, fifth and first degree (Dm7, G7, Cmaj7 in C major).
+///
+/// The dominant is always a dominant seventh chord, see `Key::dominant_seventh`. In a minor key of any form the ii and the i
+/// are built on the natural minor scale, so a minor key gives a half-diminished ii, a V7 and a minor seventh i (Bm7b5, E7, Am7 in A minor).
+///
+/// # Returns
+///
+/// The three chords in order, or an error if a chord cannot be built in the key.
+pub fn two_five_one(key: &Key) -> Result<Vec<Chord>, ()> {
+    let diatonic = match key.mode {
+        Mode::Major => key.clone(),
+        Mode::Minor(_) => Key::minor(key.tonic.clone()),
+    };
+    Ok(vec![diatonic.diatonic_seventh(2)?, key.dominant_seventh()?, diatonic.diatonic_seventh(1)?])
+}
+
+#[cfg(test)]
+mod degree_of_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use super::*;
+
+    #[test]
+    fn test_degree_of() {
+        let key = Key::major(Pitch::new_without_accidental(PitchName::D, 4));
+        assert_eq!(key.degree_of(&Pitch::new_without_accidental(PitchName::D, 2)), Some(1));
+        assert_eq!(key.degree_of(&Pitch::new(PitchName::F, 4, Accidental::Sharp)), Some(3));
+        assert_eq!(key.degree_of(&Pitch::new(PitchName::C, 5, Accidental::Sharp)), Some(7));
+        assert_eq!(key.degree_of(&Pitch::new_without_accidental(PitchName::F, 4)), None);
+    }
+}
+
+#[cfg(test)]
+mod related_keys_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    fn major(name: PitchName, accidental: Accidental) -> Key {
+        Key::major(Pitch::new(name, 4, accidental))
+    }
+
+    fn minor(name: PitchName, accidental: Accidental) -> Key {
+        Key::minor(Pitch::new(name, 4, accidental))
+    }
+
+    #[test]
+    fn test_accidental_count() {
+        assert_eq!(major(PitchName::C, Accidental::None).accidental_count(), 0);
+        assert_eq!(major(PitchName::D, Accidental::None).accidental_count(), 2);
+        assert_eq!(major(PitchName::E, Accidental::Flat).accidental_count(), -3);
+        assert_eq!(major(PitchName::C, Accidental::Sharp).accidental_count(), 7);
+        assert_eq!(minor(PitchName::A, Accidental::None).accidental_count(), 0);
+        assert_eq!(minor(PitchName::F, Accidental::Sharp).accidental_count(), 3);
+        assert_eq!(minor(PitchName::C, Accidental::None).accidental_count(), -3);
+    }
+
+    #[test]
+    fn test_relative_and_parallel() {
+        let c_major = major(PitchName::C, Accidental::None);
+        let a_minor = c_major.relative().unwrap();
+        assert_eq!(a_minor.tonic.to_string(), "A3");
+        assert_eq!(a_minor.mode, Mode::Minor(MinorForm::Natural));
+        assert_eq!(a_minor.relative().unwrap(), c_major);
+        assert_eq!(c_major.parallel(), minor(PitchName::C, Accidental::None));
+        assert_eq!(minor(PitchName::E, Accidental::None).relative().unwrap().tonic.to_string(), "G4");
+    }
+
+    #[test]
+    fn test_is_closely_related() {
+        let c_major = major(PitchName::C, Accidental::None);
+        assert!(c_major.is_closely_related(&major(PitchName::G, Accidental::None)));
+        assert!(c_major.is_closely_related(&major(PitchName::F, Accidental::None)));
+        assert!(c_major.is_closely_related(&minor(PitchName::A, Accidental::None)));
+        assert!(c_major.is_closely_related(&minor(PitchName::E, Accidental::None)));
+        assert!(c_major.is_closely_related(&minor(PitchName::D, Accidental::None)));
+        assert!(c_major.is_closely_related(&Key::new(Pitch::new_without_accidental(PitchName::C, 3), Mode::Minor(MinorForm::Harmonic))));
+        assert!(!c_major.is_closely_related(&major(PitchName::F, Accidental::Sharp)));
+        assert!(!c_major.is_closely_related(&major(PitchName::D, Accidental::None)));
+        assert!(!c_major.is_closely_related(&minor(PitchName::B, Accidental::None)));
+    }
+}
+
+#[cfg(test)]
+mod leading_tone_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_leading_tone() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert_eq!(Key::major(c4.clone()).leading_tone().to_string(), "B4");
+        assert_eq!(Key::new(c4.clone(), Mode::Minor(MinorForm::Harmonic)).leading_tone().to_string(), "B4");
+        assert_eq!(Key::new(c4.clone(), Mode::Minor(MinorForm::Melodic)).leading_tone().to_string(), "B4");
+        assert_eq!(Key::minor(c4).leading_tone().to_string(), "Bb4");
+        let g_sharp = Key::new(Pitch::new(PitchName::G, 4, Accidental::Sharp), Mode::Minor(MinorForm::Harmonic));
+        assert_eq!(g_sharp.leading_tone().to_string(), "F##5");
+    }
+}
+
+#[cfg(test)]
+mod snap_to_scale_tests {
+    use crate::theory::test_helpers::names;
+    use super::*;
+
+    fn pitches(names: &[&str]) -> Vec<Pitch> {
+        names.iter().map(|name| Pitch::try_from(*name).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_chromatic_melody() {
+        let key = Key::major(Pitch::new_without_accidental(PitchName::C, 4));
+        let melody = pitches(&["C4", "C#4", "Eb4", "E4", "F#4", "G4", "Ab4", "Bb4", "B#4"]);
+        let snapped = snap_melody_to_scale(&melody, &key);
+        assert_eq!(names(&snapped), vec!["C4", "C4", "E4", "E4", "F4", "G4", "A4", "B4", "C5"]);
+        assert!(snapped.iter().all(|pitch| key.degree_of(pitch).is_some()));
+    }
+
+    #[test]
+    fn test_minor_key() {
+        let key = Key::new(Pitch::new_without_accidental(PitchName::A, 3), Mode::Minor(MinorForm::Harmonic));
+        let melody = pitches(&["A3", "Bb3", "F#4", "G4", "Ab4"]);
+        assert_eq!(names(&snap_melody_to_scale(&melody, &key)), vec!["A3", "B3", "F4", "G#4", "G#4"]);
+    }
+}
+
+#[cfg(test)]
+mod spell_for_notation_tests {
+    use crate::theory::pitch::PitchName;
+    use crate::theory::test_helpers::names;
+    use super::*;
+
+    fn chromatic_run(from: Pitch) -> Vec<Pitch> {
+        (0..=12).map(|semitones| Pitch::try_from(f32::from(from.clone()) + semitones as f32 * 0.5).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_chromatic_run_with_sharps() {
+        let d4 = Pitch::new_without_accidental(PitchName::D, 4);
+        let spelled = spell_for_notation(&chromatic_run(d4.clone()), &Key::major(d4));
+        assert_eq!(names(&spelled), vec!["D4", "D#4", "E4", "F4", "F#4", "G4", "G#4", "A4", "A#4", "B4", "C5", "C#5", "D5"]);
+    }
+
+    #[test]
+    fn test_chromatic_run_with_flats() {
+        let b_flat = Pitch::new(PitchName::B, 3, Accidental::Flat);
+        let spelled = spell_for_notation(&chromatic_run(b_flat.clone()), &Key::major(b_flat));
+        assert_eq!(names(&spelled), vec!["Bb3", "B3", "C4", "Db4", "D4", "Eb4", "E4", "F4", "Gb4", "G4", "Ab4", "A4", "Bb4"]);
+    }
+
+    #[test]
+    fn test_diatonic_spelling() {
+        let key = Key::major(Pitch::new_without_accidental(PitchName::D, 4));
+        let pitches = vec![Pitch::new(PitchName::G, 4, Accidental::Flat), Pitch::new(PitchName::B, 4, Accidental::Sharp)];
+        assert_eq!(names(&spell_for_notation(&pitches, &key)), vec!["F#4", "C5"]);
+    }
+}
+
+#[cfg(test)]
+mod classify_pitch_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_classify_pitch() {
+        let key = Key::major(Pitch::new_without_accidental(PitchName::C, 4));
+        assert_eq!(key.classify_pitch(&Pitch::new_without_accidental(PitchName::C, 2)), PitchFunction::TonicChordTone(1));
+        assert_eq!(key.classify_pitch(&Pitch::new_without_accidental(PitchName::G, 5)), PitchFunction::TonicChordTone(5));
+        assert_eq!(key.classify_pitch(&Pitch::new_without_accidental(PitchName::D, 4)), PitchFunction::Diatonic(2));
+        assert_eq!(key.classify_pitch(&Pitch::new(PitchName::C, 4, Accidental::Sharp)), PitchFunction::Chromatic);
+    }
+}
+
+#[cfg(test)]
+mod all_scales_tests {
+    use crate::theory::pitch::PitchName;
+    use crate::theory::test_helpers::names;
+    use super::*;
+
+    #[test]
+    fn test_circle_of_fifths() {
+        let tonics = names(&circle_of_fifths());
+        assert_eq!(tonics, vec!["C4", "G4", "D4", "A4", "E4", "B4", "F#4", "Db4", "Ab4", "Eb4", "Bb4", "F4"]);
+    }
+
+    #[test]
+    fn test_all_major_scales() {
+        let scales = all_major_scales();
+        assert_eq!(scales.len(), 12);
+        let (tonic, pitches) = &scales[7];
+        assert_eq!(tonic, &Pitch::new(PitchName::D, 4, Accidental::Flat));
+        assert_eq!(names(pitches), vec!["Db4", "Eb4", "F4", "Gb4", "Ab4", "Bb4", "C5"]);
+    }
+
+    #[test]
+    fn test_all_minor_scales() {
+        let scales = all_minor_scales();
+        assert_eq!(scales.len(), 12);
+        let tonics: Vec<Pitch> = scales.iter().map(|(tonic, _)| tonic.clone()).collect();
+        assert_eq!(names(&tonics), vec!["A4", "E4", "B4", "F#4", "C#4", "G#4", "D#4", "Bb4", "F4", "C4", "G4", "D4"]);
+        assert_eq!(names(&scales[1].1), vec!["E4", "F#4", "G4", "A4", "B4", "C5", "D5"]);
+    }
+}
+
+#[cfg(test)]
+mod cadence_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    fn symbols(chords: &[Chord]) -> Vec<String> {
+        chords.iter().map(|chord| chord.to_string()).collect()
+    }
+
+    #[test]
+    fn test_diatonic_triads() {
+        let key = Key::major(Pitch::new_without_accidental(PitchName::C, 4));
+        assert_eq!(symbols(&key.diatonic_triads().unwrap()), vec!["C", "Dm", "Em", "F", "G", "Am", "Bdim"]);
+        let key = Key::new(Pitch::new_without_accidental(PitchName::A, 4), Mode::Minor(MinorForm::Harmonic));
+        assert_eq!(symbols(&key.diatonic_triads().unwrap()), vec!["Am", "Bdim", "Caug", "Dm", "E", "F", "G#dim"]);
+    }
+
+    #[test]
+    fn test_major_key() {
+        let key = Key::major(Pitch::new_without_accidental(PitchName::C, 4));
+        assert_eq!(symbols(&cadence(&key, CadenceKind::Authentic).unwrap()), vec!["G7", "C"]);
+        assert_eq!(symbols(&cadence(&key, CadenceKind::Plagal).unwrap()), vec!["F", "C"]);
+        assert_eq!(symbols(&cadence(&key, CadenceKind::Half).unwrap()), vec!["C", "G"]);
+        assert_eq!(symbols(&cadence(&key, CadenceKind::Deceptive).unwrap()), vec!["G7", "Am"]);
+    }
+
+    #[test]
+    fn test_minor_key() {
+        let key = Key::minor(Pitch::new_without_accidental(PitchName::A, 4));
+        assert_eq!(symbols(&cadence(&key, CadenceKind::Authentic).unwrap()), vec!["E7", "Am"]);
+        assert_eq!(symbols(&cadence(&key, CadenceKind::Half).unwrap()), vec!["Am", "E"]);
+        assert_eq!(symbols(&cadence(&key, CadenceKind::Deceptive).unwrap()), vec!["E7", "F"]);
+    }
+}
+
+#[cfg(test)]
+mod two_five_one_tests {
+    use crate::theory::pitch::PitchName;
+    use crate::theory::test_helpers::names;
+    use super::*;
+
+    #[test]
+    fn test_c_major() {
+        let chords = two_five_one(&Key::major(Pitch::new_without_accidental(PitchName::C, 4))).unwrap();
+        let symbols: Vec<String> = chords.iter().map(|chord| chord.to_string()).collect();
+        assert_eq!(symbols, vec!["Dm7", "G7", "Cmaj7"]);
+        assert_eq!(names(chords[0].pitches()), vec!["D4", "F4", "A4", "C5"]);
+        assert_eq!(names(chords[1].pitches()), vec!["G4", "B4", "D5", "F5"]);
+        assert_eq!(names(chords[2].pitches()), vec!["C4", "E4", "G4", "B4"]);
+    }
+
+    #[test]
+    fn test_spelling() {
+        let chords = two_five_one(&Key::major(Pitch::new(PitchName::E, 4, Accidental::Flat))).unwrap();
+        let symbols: Vec<String> = chords.iter().map(|chord| chord.to_string()).collect();
+        assert_eq!(symbols, vec!["Fm7", "Bb7", "Ebmaj7"]);
+        assert_eq!(names(chords[0].pitches()), vec!["F4", "Ab4", "C5", "Eb5"]);
+        let chords = two_five_one(&Key::minor(Pitch::new_without_accidental(PitchName::A, 4))).unwrap();
+        let symbols: Vec<String> = chords.iter().map(|chord| chord.to_string()).collect();
+        assert_eq!(symbols, vec!["Bm7b5", "E7", "Am7"]);
+    }
+
+    #[test]
+    fn test_harmonic_and_melodic_minor() {
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        for form in [MinorForm::Harmonic, MinorForm::Melodic] {
+            let chords = two_five_one(&Key::new(a4.clone(), Mode::Minor(form))).unwrap();
+            let symbols: Vec<String> = chords.iter().map(|chord| chord.to_string()).collect();
+            assert_eq!(symbols, vec!["Bm7b5", "E7", "Am7"]);
+            assert_eq!(names(chords[1].pitches()), vec!["E5", "G#5", "B5", "D6"]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod dominant_seventh_tests {
+    use crate::theory::pitch::PitchName;
+    use crate::theory::test_helpers::names;
+    use super::*;
+
+    #[test]
+    fn test_major() {
+        let chord = Key::major(Pitch::new_without_accidental(PitchName::C, 4)).dominant_seventh().unwrap();
+        assert_eq!(chord.to_string(), "G7");
+        assert_eq!(names(chord.pitches()), vec!["G4", "B4", "D5", "F5"]);
+    }
+
+    #[test]
+    fn test_minor_uses_leading_tone() {
+        let key = Key::minor(Pitch::new_without_accidental(PitchName::A, 3));
+        let chord = key.dominant_seventh().unwrap();
+        assert_eq!(names(chord.pitches()), vec!["E4", "G#4", "B4", "D5"]);
+        assert_eq!(PitchClass::from(chord.pitches()[1].clone()), PitchClass::from(Key::new(key.tonic, Mode::Minor(MinorForm::Harmonic)).leading_tone()));
+    }
+}
+
+#[cfg(test)]
+mod scale_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_default_minor_is_natural() {
+        let key = Key::minor(Pitch::new_without_accidental(PitchName::A, 4));
+        assert_eq!(key.mode, Mode::Minor(MinorForm::Natural));
+        assert_eq!(key.scale().degree_offsets(), vec![0, 2, 3, 5, 7, 8, 10]);
+    }
+
+    #[test]
+    fn test_major() {
+        let key = Key::major(Pitch::new_without_accidental(PitchName::C, 4));
+        assert_eq!(key.scale().degree_offsets(), vec![0, 2, 4, 5, 7, 9, 11]);
+    }
+
+    #[test]
+    fn test_harmonic_minor_raises_7th() {
+        let tonic = Pitch::new_without_accidental(PitchName::A, 4);
+        let natural = Key::minor(tonic.clone()).scale().degree_offsets();
+        let harmonic = Key::new(tonic, Mode::Minor(MinorForm::Harmonic)).scale().degree_offsets();
+        assert_eq!(harmonic[6], natural[6] + 1);
+        assert_eq!(harmonic[..6], natural[..6]);
+    }
+
+    #[test]
+    fn test_melodic_minor_raises_6th_and_7th() {
+        let tonic = Pitch::new_without_accidental(PitchName::A, 4);
+        let natural = Key::minor(tonic.clone()).scale().degree_offsets();
+        let melodic = Key::new(tonic, Mode::Minor(MinorForm::Melodic)).scale().degree_offsets();
+        assert_eq!(melodic[5], natural[5] + 1);
+        assert_eq!(melodic[6], natural[6] + 1);
+        assert_eq!(melodic[..5], natural[..5]);
+    }
+}
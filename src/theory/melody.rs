@@ -0,0 +1,473 @@
+use std::time::Duration;
+use crate::theory::interval::{Interval, IntervalStep};
+use crate::theory::key::Key;
+use crate::theory::pitch::Pitch;
+
+/// A note of a melody: a pitch, or a rest when there is no pitch, held for a duration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note {
+    pub pitch: Option<Pitch>,
+    pub duration: Duration,
+    /// How hard the note is played, from 0.0 to 1.0
+    pub velocity: f32,
+}
+
+impl Note {
+    pub fn new(pitch: Pitch, duration: Duration) -> Self {
+        Self {
+            pitch: Some(pitch),
+            duration,
+            velocity: 1.0,
+        }
+    }
+    pub fn rest(duration: Duration) -> Self {
+        Self {
+            pitch: None,
+            duration,
+            velocity: 0.0,
+        }
+    }
+    pub fn is_rest(&self) -> bool {
+        self.pitch.is_none()
+    }
+}
+
+/// The direction from one pitch of a melody to the next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Contour {
+    Up,
+    Down,
+    Same,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Melody {
+    pub notes: Vec<Note>,
+}
+
+impl Melody {
+    pub fn new(notes: Vec<Note>) -> Self {
+        Self { notes }
+    }
+
+    /// Merges consecutive notes of the same pitch into one sustained note, summing their durations,
+    /// so the pitch is not attacked again. Consecutive rests are merged as well.
+    ///
+    /// The merged note keeps the velocity and the spelling of the first of the notes.
+    pub fn merge_ties(&self) -> Melody {
+        let mut notes: Vec<Note> = Vec::with_capacity(self.notes.len());
+        for note in &self.notes {
+            match notes.last_mut() {
+                Some(last) if last.pitch == note.pitch => last.duration += note.duration,
+                _ => notes.push(note.clone()),
+            }
+        }
+        Melody::new(notes)
+    }
+
+    /// Transposes every note of the melody by a number of semitones, see `Pitch::transpose_semitones`.
+    ///
+    /// Durations, velocities and rests are left untouched.
+    pub fn transpose(&self, semitones: i32) -> Melody {
+        let notes = self.notes.iter()
+            .map(|note| Note {
+                pitch: note.pitch.as_ref().map(|pitch| pitch.transpose_semitones(semitones)),
+                ..note.clone()
+            })
+            .collect();
+        Melody::new(notes)
+    }
+
+    /// Transposes every note of the melody by a number of scale degrees within a key.
+    ///
+    /// Durations, velocities and rests are left untouched.
+    ///
+    /// # Returns
+    ///
+    /// The transposed melody, or an error if a note is not diatonic to the key.
+    pub fn transpose_diatonic(&self, key: &Key, degrees: i32) -> Result<Melody, ()> {
+        let offsets: Vec<i32> = key.scale().degree_offsets().iter().map(|offset| *offset as i32).collect();
+        let length = offsets.len() as i32;
+        let mut notes = Vec::with_capacity(self.notes.len());
+        for note in &self.notes {
+            let pitch = match &note.pitch {
+                Some(pitch) => pitch,
+                None => {
+                    notes.push(note.clone());
+                    continue;
+                }
+            };
+            let index = key.degree_of(pitch).ok_or(())? as i32 - 1;
+            let target = index + degrees;
+            let semitones = offsets[target.rem_euclid(length) as usize] - offsets[index as usize] + target.div_euclid(length) * 12;
+            notes.push(Note {
+                pitch: Some(pitch.spell_at(degrees as i16, semitones as i16)?),
+                ..note.clone()
+            });
+        }
+        Ok(Melody::new(notes))
+    }
+}
+
+/// Snaps the duration of every note of a melody to the nearest multiple of a grid.
+///
+/// Notes are never quantized away: durations shorter than half the grid snap up to one grid unit.
+///
+/// # Arguments
+///
+/// * `melody` - The melody to quantize
+/// * `grid` - The grid unit, e.g. the duration of a sixteenth note
+pub fn quantize_durations(melody: &Melody, grid: Duration) -> Melody {
+    if grid.is_zero() {
+        return melody.clone();
+    }
+    let notes = melody.notes.iter()
+        .map(|note| {
+            let units = (note.duration.as_secs_f64() / grid.as_secs_f64()).round().max(1.0);
+            Note {
+                duration: grid * units as u32,
+                ..note.clone()
+            }
+        })
+        .collect();
+    Melody::new(notes)
+}
+
+/// Calculates the signed number of semitones from each pitch to the next one.
+///
+/// # Returns
+///
+/// One step less than there are pitches, positive for ascending steps (+4 for a major third up)
+/// and negative for descending steps (-2 for a whole step down).
+pub fn melodic_intervals(pitches: &[Pitch]) -> Vec<i32> {
+    pitches.windows(2)
+        .map(|pair| ((f32::from(pair[1].clone()) - f32::from(pair[0].clone())) / f32::from(IntervalStep::Half)).round() as i32)
+        .collect()
+}
+
+/// Reduces a melody to the direction of each step, ignoring the size of the steps, see `melodic_intervals`.
+pub fn melodic_contour(pitches: &[Pitch]) -> Vec<Contour> {
+    melodic_intervals(pitches)
+        .into_iter()
+        .map(|semitones| match semitones.signum() {
+            1 => Contour::Up,
+            -1 => Contour::Down,
+            _ => Contour::Same,
+        })
+        .collect()
+}
+
+/// Calculates the ambitus (range) of a melody.
+///
+/// # Returns
+///
+/// The interval between the lowest and the highest pitch, or `None` if there are no pitches.
+pub fn ambitus(pitches: &[Pitch]) -> Option<Interval> {
+    let lowest = pitches.iter().min()?;
+    let highest = pitches.iter().max()?;
+    Some(Interval::new(lowest.clone(), highest.clone()))
+}
+
+/// Calculates the interval from the first to the last pitch of a phrase, e.g. to tell whether it ends higher than it began.
+///
+/// Unlike the ambitus, see `ambitus`, the pitches in between are ignored.
+/// An `Interval` has no direction, so the direction is returned alongside it.
+///
+/// # Returns
+///
+/// The interval and whether the last pitch lies above (`Contour::Up`), below (`Contour::Down`) or at (`Contour::Same`)
+/// the first one, or `None` if there are no pitches.
+pub fn phrase_span(pitches: &[Pitch]) -> Option<(Interval, Contour)> {
+    let first = pitches.first()?;
+    let last = pitches.last()?;
+    let direction = match last.cmp(first) {
+        std::cmp::Ordering::Greater => Contour::Up,
+        std::cmp::Ordering::Less => Contour::Down,
+        std::cmp::Ordering::Equal => Contour::Same,
+    };
+    Some((Interval::new(first.clone(), last.clone()), direction))
+}
+
+/// Checks whether a melody spans at most an octave (12 semitones) from its lowest to its highest pitch.
+pub fn fits_in_octave(pitches: &[Pitch]) -> bool {
+    match ambitus(pitches) {
+        Some(interval) => interval.get_number_of_semitones(false) <= 12,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod transpose_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use super::*;
+
+    fn melody() -> Melody {
+        Melody::new(vec![
+            Note::new(Pitch::new_without_accidental(PitchName::C, 4), Duration::from_millis(500)),
+            Note::rest(Duration::from_millis(250)),
+            Note::new(Pitch::new_without_accidental(PitchName::E, 4), Duration::from_millis(250)),
+            Note::new(Pitch::new_without_accidental(PitchName::G, 4), Duration::from_millis(1000)),
+        ])
+    }
+
+    #[test]
+    fn test_up_an_octave() {
+        let melody = melody();
+        let transposed = melody.transpose(12);
+        let pitches: Vec<Option<String>> = transposed.notes.iter().map(|note| note.pitch.as_ref().map(|p| p.to_string())).collect();
+        assert_eq!(pitches, vec![Some("C5".to_string()), None, Some("E5".to_string()), Some("G5".to_string())]);
+        for (original, transposed) in melody.notes.iter().zip(transposed.notes.iter()) {
+            assert_eq!(original.duration, transposed.duration);
+            assert_eq!(original.velocity, transposed.velocity);
+        }
+    }
+
+    #[test]
+    fn test_chromatic() {
+        let transposed = melody().transpose(-2);
+        assert_eq!(transposed.notes[0].pitch, Some(Pitch::new(PitchName::B, 3, Accidental::Flat)));
+        assert_eq!(transposed.notes[3].pitch, Some(Pitch::new_without_accidental(PitchName::F, 4)));
+    }
+
+    #[test]
+    fn test_diatonic() {
+        let key = Key::major(Pitch::new_without_accidental(PitchName::C, 4));
+        let transposed = melody().transpose_diatonic(&key, 1).unwrap();
+        let pitches: Vec<Option<String>> = transposed.notes.iter().map(|note| note.pitch.as_ref().map(|p| p.to_string())).collect();
+        assert_eq!(pitches, vec![Some("D4".to_string()), None, Some("F4".to_string()), Some("A4".to_string())]);
+
+        let transposed = melody().transpose_diatonic(&key, -3).unwrap();
+        let pitches: Vec<Option<String>> = transposed.notes.iter().map(|note| note.pitch.as_ref().map(|p| p.to_string())).collect();
+        assert_eq!(pitches, vec![Some("G3".to_string()), None, Some("B3".to_string()), Some("D4".to_string())]);
+    }
+
+    #[test]
+    fn test_diatonic_chromatic_note() {
+        let key = Key::major(Pitch::new_without_accidental(PitchName::C, 4));
+        let melody = Melody::new(vec![Note::new(Pitch::new(PitchName::C, 4, Accidental::Sharp), Duration::from_millis(500))]);
+        assert!(melody.transpose_diatonic(&key, 1).is_err());
+    }
+}
+
+#[cfg(test)]
+mod merge_ties_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let quarter = Duration::from_millis(500);
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let mut last = Note::new(c4.clone(), quarter);
+        last.velocity = 0.5;
+        let melody = Melody::new(vec![
+            Note::new(c4.clone(), quarter),
+            Note::new(c4.clone(), quarter),
+            last,
+        ]);
+        let merged = melody.merge_ties();
+        assert_eq!(merged.notes, vec![Note::new(c4, quarter * 3)]);
+    }
+
+    #[test]
+    fn test_keep_different_pitches() {
+        let quarter = Duration::from_millis(500);
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let d4 = Pitch::new_without_accidental(PitchName::D, 4);
+        let melody = Melody::new(vec![
+            Note::new(c4.clone(), quarter),
+            Note::new(d4.clone(), quarter),
+            Note::rest(quarter),
+            Note::rest(quarter),
+            Note::new(c4.clone(), quarter),
+        ]);
+        let merged = melody.merge_ties();
+        assert_eq!(merged.notes, vec![
+            Note::new(c4.clone(), quarter),
+            Note::new(d4, quarter),
+            Note::rest(quarter * 2),
+            Note::new(c4, quarter),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod melodic_intervals_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use super::*;
+
+    #[test]
+    fn test_ascending() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+            Pitch::new_without_accidental(PitchName::G, 4),
+            Pitch::new_without_accidental(PitchName::C, 5),
+        ];
+        assert_eq!(melodic_intervals(&pitches), vec![4, 3, 5]);
+    }
+
+    #[test]
+    fn test_descending() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::D, 5),
+            Pitch::new_without_accidental(PitchName::C, 5),
+            Pitch::new(PitchName::B, 4, Accidental::Flat),
+            Pitch::new(PitchName::A, 4, Accidental::Sharp),
+            Pitch::new_without_accidental(PitchName::D, 3),
+        ];
+        assert_eq!(melodic_intervals(&pitches), vec![-2, -2, 0, -20]);
+        assert!(melodic_intervals(&pitches[..1]).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod melodic_contour_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use super::*;
+
+    #[test]
+    fn test_arch() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+            Pitch::new_without_accidental(PitchName::A, 4),
+            Pitch::new_without_accidental(PitchName::A, 4),
+            Pitch::new_without_accidental(PitchName::F, 4),
+            Pitch::new_without_accidental(PitchName::D, 4),
+        ];
+        assert_eq!(melodic_contour(&pitches), vec![Contour::Up, Contour::Up, Contour::Same, Contour::Down, Contour::Down]);
+    }
+
+    #[test]
+    fn test_enharmonic_is_same() {
+        let pitches = vec![Pitch::new(PitchName::C, 4, Accidental::Sharp), Pitch::new(PitchName::D, 4, Accidental::Flat)];
+        assert_eq!(melodic_contour(&pitches), vec![Contour::Same]);
+        assert!(melodic_contour(&[]).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod quantize_durations_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_sixteenth_grid() {
+        // a sixteenth note at 120 bpm
+        let grid = Duration::from_millis(125);
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let melody = Melody::new(vec![
+            Note::new(c4.clone(), Duration::from_millis(130)),
+            Note::new(c4.clone(), Duration::from_millis(240)),
+            Note::rest(Duration::from_millis(370)),
+            Note::new(c4.clone(), Duration::from_millis(510)),
+        ]);
+        let durations: Vec<Duration> = quantize_durations(&melody, grid).notes.iter().map(|note| note.duration).collect();
+        assert_eq!(durations, vec![
+            Duration::from_millis(125),
+            Duration::from_millis(250),
+            Duration::from_millis(375),
+            Duration::from_millis(500),
+        ]);
+    }
+
+    #[test]
+    fn test_short_durations_snap_up() {
+        let grid = Duration::from_millis(125);
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let melody = Melody::new(vec![
+            Note::new(c4.clone(), Duration::from_millis(40)),
+            Note::new(c4.clone(), Duration::ZERO),
+        ]);
+        let quantized = quantize_durations(&melody, grid);
+        assert!(quantized.notes.iter().all(|note| note.duration == grid));
+        assert_eq!(quantized.notes[0].pitch, Some(c4));
+    }
+}
+
+#[cfg(test)]
+mod ambitus_tests {
+    use crate::theory::interval::IntervalQuality;
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_ambitus() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::E, 4),
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::A, 4),
+            Pitch::new_without_accidental(PitchName::D, 4),
+        ];
+        let interval = ambitus(&pitches).unwrap();
+        assert_eq!(interval.get_specific_interval(), Ok((6, IntervalQuality::Major, false)));
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(ambitus(&[]).is_none());
+    }
+}
+
+#[cfg(test)]
+mod phrase_span_tests {
+    use crate::theory::interval::IntervalQuality;
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_ascending() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::A, 4),
+            Pitch::new_without_accidental(PitchName::D, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+        ];
+        let (interval, direction) = phrase_span(&pitches).unwrap();
+        assert_eq!(direction, Contour::Up);
+        assert_eq!(interval.get_specific_interval(), Ok((3, IntervalQuality::Major, false)));
+        let (_, direction) = phrase_span(&[pitches[3].clone(), pitches[0].clone()]).unwrap();
+        assert_eq!(direction, Contour::Down);
+    }
+
+    #[test]
+    fn test_return_to_start() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::G, 4),
+            Pitch::new_without_accidental(PitchName::B, 4),
+            Pitch::new_without_accidental(PitchName::G, 4),
+        ];
+        let (interval, direction) = phrase_span(&pitches).unwrap();
+        assert_eq!(direction, Contour::Same);
+        assert!(interval.is_unison());
+        assert!(phrase_span(&[]).is_none());
+    }
+}
+
+#[cfg(test)]
+mod fits_in_octave_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    #[test]
+    fn test_within_octave() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::G, 4),
+            Pitch::new_without_accidental(PitchName::C, 5),
+            Pitch::new_without_accidental(PitchName::E, 4),
+        ];
+        assert!(fits_in_octave(&pitches));
+    }
+
+    #[test]
+    fn test_exceeding_octave() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::G, 4),
+            Pitch::new_without_accidental(PitchName::D, 5),
+        ];
+        assert!(!fits_in_octave(&pitches));
+    }
+}
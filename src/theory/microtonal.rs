@@ -0,0 +1,91 @@
+use std::fmt::{Display, Formatter};
+use crate::theory::interval::IntervalStep;
+use crate::theory::pitch::Pitch;
+
+/// A pitch detuned by a number of cents, for tunings finer than the equal-tempered semitone.
+///
+/// A cent is a hundredth of a semitone, so +50 cents lies halfway between the pitch and the next semitone up.
+#[derive(Clone, Debug)]
+pub struct MicrotonalPitch {
+    pub pitch: Pitch,
+    pub cents: f32,
+}
+
+impl MicrotonalPitch {
+    pub fn new(pitch: Pitch, cents: f32) -> Self {
+        Self { pitch, cents }
+    }
+
+    /// Calculates the frequency of the pitch with A4 = 440Hz, see `Pitch::to_hertz`.
+    pub fn to_hertz(&self) -> f32 {
+        self.to_hertz_with_reference(440.0)
+    }
+
+    /// Calculates the frequency of the pitch, tuned so that A4 sounds at `reference` hertz.
+    pub fn to_hertz_with_reference(&self, reference: f32) -> f32 {
+        self.pitch.to_hertz_with_reference(reference) * 2.0_f32.powf(self.cents / 1200.0)
+    }
+}
+
+impl From<Pitch> for MicrotonalPitch {
+    fn from(value: Pitch) -> Self {
+        Self::new(value, 0.0)
+    }
+}
+
+impl From<MicrotonalPitch> for f32 {
+    /// The value of the pitch on the scale of `From<Pitch> for f32`, with the cents as a fraction of a semitone.
+    fn from(value: MicrotonalPitch) -> Self {
+        f32::from(value.pitch) + value.cents / 100.0 * f32::from(IntervalStep::Half)
+    }
+}
+
+impl Display for MicrotonalPitch {
+    /// Writes the pitch followed by its signed cents, e.g. "A4+50c" or "C4-14c".
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{:+}c", self.pitch, self.cents)
+    }
+}
+
+impl PartialEq<Self> for MicrotonalPitch {
+    fn eq(&self, other: &Self) -> bool {
+        f32::from(self.clone()) == f32::from(other.clone())
+    }
+}
+
+impl PartialOrd<Self> for MicrotonalPitch {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        f32::from(self.clone()).partial_cmp(&f32::from(other.clone()))
+    }
+}
+
+#[cfg(test)]
+mod microtonal_pitch_tests {
+    use crate::theory::pitch::{Accidental, PitchName};
+    use super::*;
+
+    #[test]
+    fn test_to_hertz() {
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        let pitch = MicrotonalPitch::new(a4.clone(), 50.0);
+        assert!((pitch.to_hertz() - 452.89).abs() < 0.01);
+        assert_eq!(MicrotonalPitch::from(a4.clone()).to_hertz(), a4.to_hertz());
+        assert!((MicrotonalPitch::new(a4, -1200.0).to_hertz() - 220.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_cmp() {
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        let sharp = MicrotonalPitch::new(a4.clone(), 50.0);
+        assert!(sharp > MicrotonalPitch::from(a4.clone()));
+        assert!(sharp < MicrotonalPitch::from(Pitch::new(PitchName::A, 4, Accidental::Sharp)));
+        assert_eq!(MicrotonalPitch::new(a4, 100.0), MicrotonalPitch::from(Pitch::new(PitchName::B, 4, Accidental::Flat)));
+    }
+
+    #[test]
+    fn test_display() {
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        assert_eq!(MicrotonalPitch::new(a4.clone(), 50.0).to_string(), "A4+50c");
+        assert_eq!(MicrotonalPitch::new(a4, -13.5).to_string(), "A4-13.5c");
+    }
+}
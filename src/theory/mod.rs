@@ -1,4 +1,11 @@
 pub mod pitch;
+pub mod pitch_class;
+pub mod microtonal;
 pub mod interval;
 pub mod chord;
-pub mod scale;
\ No newline at end of file
+pub mod scale;
+pub mod key;
+pub mod melody;
+
+#[cfg(test)]
+mod test_helpers;
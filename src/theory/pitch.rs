@@ -1,11 +1,21 @@
 use std::fmt::{Display, Formatter};
 use std::io::Error;
-use std::ops::Sub;
+use std::ops::{Add, Sub};
+use std::sync::LazyLock;
 use regex::Regex;
 use crate::theory::interval::{Interval, IntervalStep};
+use crate::theory::pitch_class::PitchClass;
 use crate::utils::float_mod;
 
+/// The pattern of a pitch like "C#4" or "A-1", compiled once for all parses.
+static PITCH_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([A-G])(#{1,3}|b{1,3})?(0|-?[1-9]\d?)$").unwrap());
+
+/// The pattern of a pitch in German naming like "Fis4", "Es4" or "H3", see `Pitch::try_from_german`.
+static GERMAN_PITCH_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-H])((?:is){1,3}|(?:es){1,3}|s(?:es){0,2})?(0|-?[1-9]\d?)$").unwrap());
+
 #[derive(Clone, PartialEq, Debug, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PitchName {
     C,
     D,
@@ -47,13 +57,72 @@ impl TryFrom<String> for PitchName {
     }
 }
 
+impl PitchName {
+    /// The position of the letter name within the octave, from 0 (C) to 6 (B).
+    pub fn position(&self) -> u8 {
+        match self {
+            PitchName::C => 0,
+            PitchName::D => 1,
+            PitchName::E => 2,
+            PitchName::F => 3,
+            PitchName::G => 4,
+            PitchName::A => 5,
+            PitchName::B => 6,
+        }
+    }
+
+    /// The letter name at the given position within the octave, wrapping around after B.
+    pub fn from_position(position: u8) -> Self {
+        match position % 7 {
+            0 => PitchName::C,
+            1 => PitchName::D,
+            2 => PitchName::E,
+            3 => PitchName::F,
+            4 => PitchName::G,
+            5 => PitchName::A,
+            _ => PitchName::B,
+        }
+    }
+
+    /// Parses a fixed-do solfège syllable, ignoring case: Do is C, Re is D, ..., La is A.
+    ///
+    /// B is accepted both as "Si", as in most of Europe, and as "Ti", as in English-speaking countries.
+    pub fn from_solfege(value: &str) -> Result<Self, ()> {
+        match value.to_lowercase().as_str() {
+            "do" => Ok(PitchName::C),
+            "re" => Ok(PitchName::D),
+            "mi" => Ok(PitchName::E),
+            "fa" => Ok(PitchName::F),
+            "sol" => Ok(PitchName::G),
+            "la" => Ok(PitchName::A),
+            "si" | "ti" => Ok(PitchName::B),
+            _ => Err(()),
+        }
+    }
+
+    /// The fixed-do solfège syllable of the letter name, with "Si" for B, see `from_solfege`.
+    pub fn to_solfege(&self) -> &'static str {
+        match self {
+            PitchName::C => "Do",
+            PitchName::D => "Re",
+            PitchName::E => "Mi",
+            PitchName::F => "Fa",
+            PitchName::G => "Sol",
+            PitchName::A => "La",
+            PitchName::B => "Si",
+        }
+    }
+}
 
 #[derive(Clone, PartialEq, Debug, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Accidental {
     Sharp,
     Flat,
     DoubleSharp,
     DoubleFlat,
+    TripleSharp,
+    TripleFlat,
     None,
 }
 
@@ -64,6 +133,8 @@ impl Display for Accidental {
             Accidental::Flat => "b",
             Accidental::DoubleSharp => "##",
             Accidental::DoubleFlat => "bb",
+            Accidental::TripleSharp => "###",
+            Accidental::TripleFlat => "bbb",
             Accidental::None => "",
         })
     }
@@ -78,12 +149,61 @@ impl TryFrom<String> for Accidental {
             "b" => Ok(Accidental::Flat),
             "##" => Ok(Accidental::DoubleSharp),
             "bb" => Ok(Accidental::DoubleFlat),
+            "###" => Ok(Accidental::TripleSharp),
+            "bbb" => Ok(Accidental::TripleFlat),
             "" => Ok(Accidental::None),
             _ => Err(()),
         }
     }
 }
 
+/// Named tuning standards, by the frequency of A4.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TuningStandard {
+    /// A4 at 440 Hz, the modern concert pitch
+    ConcertA440,
+    /// A4 at 415 Hz, common for baroque music
+    Baroque415,
+    /// A4 at 430 Hz, common for classical music
+    Classical430,
+    /// A4 at 432 Hz
+    Verdi432,
+}
+
+impl TuningStandard {
+    /// The frequency of A4 in hertz.
+    pub fn reference_hertz(&self) -> f32 {
+        match self {
+            TuningStandard::ConcertA440 => 440.0,
+            TuningStandard::Baroque415 => 415.0,
+            TuningStandard::Classical430 => 430.0,
+            TuningStandard::Verdi432 => 432.0,
+        }
+    }
+}
+
+/// Clefs of the five-line staff, by the pitch on their bottom line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Clef {
+    /// The G clef on the second line, E4 on the bottom line
+    Treble,
+    /// The F clef on the fourth line, G2 on the bottom line
+    Bass,
+    /// The C clef on the middle line, F3 on the bottom line
+    Alto,
+}
+
+impl Clef {
+    /// The pitch on the bottom line of the staff.
+    pub fn bottom_line(&self) -> Pitch {
+        match self {
+            Clef::Treble => Pitch::new_without_accidental(PitchName::E, 4),
+            Clef::Bass => Pitch::new_without_accidental(PitchName::G, 2),
+            Clef::Alto => Pitch::new_without_accidental(PitchName::F, 3),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Pitch {
     pub name: PitchName,
@@ -101,8 +221,15 @@ impl TryFrom<String> for Pitch {
     type Error = ();
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let re = Regex::new(r"^([A-G])(#{1,2}|b{1,2})?([0-9]|[1-9]\d)$").unwrap();
-        if let Some(captures) = re.captures(&value) {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl TryFrom<&str> for Pitch {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if let Some(captures) = PITCH_REGEX.captures(value) {
             let name = captures.get(1).ok_or(())?.as_str();
             let accidental = captures.get(2).map_or("", |m| m.as_str());
             let octave = captures.get(3).ok_or(())?.as_str();
@@ -117,6 +244,23 @@ impl TryFrom<String> for Pitch {
     }
 }
 
+/// Serializes the pitch as its name, e.g. "C#4", so that saved pitches stay readable.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pitch {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes a pitch from its name, see `TryFrom<&str> for Pitch`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Pitch {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Pitch::try_from(name.as_str()).map_err(|_| serde::de::Error::custom(format!("{} is not a valid pitch", name)))
+    }
+}
+
 impl Pitch {
     pub fn new(name: PitchName, octave: i8, accidental: Accidental) -> Self {
         Self {
@@ -133,9 +277,129 @@ impl Pitch {
         }
     }
     pub fn to_hertz(&self) -> f32 {
+        self.to_hertz_with_reference(440.0)
+    }
+    /// Calculates the equal-tempered frequency of the pitch, tuned so that A4 sounds at `reference` hertz.
+    pub fn to_hertz_with_reference(&self, reference: f32) -> f32 {
         let standard_pitch = Pitch::new_without_accidental(PitchName::A, 4);
         let number_of_semitones = (f32::from(self.clone()) - f32::from(standard_pitch)) / f32::from(IntervalStep::Half);
-        440.0 * 2.0_f32.powf(number_of_semitones / 12.0)
+        reference * 2.0_f32.powf(number_of_semitones / 12.0)
+    }
+    /// Calculates the equal-tempered frequency of the pitch in a named tuning standard.
+    pub fn to_hertz_standard(&self, standard: TuningStandard) -> f32 {
+        self.to_hertz_with_reference(standard.reference_hertz())
+    }
+    /// Finds the equal-tempered pitch nearest to a frequency, e.g. for a tuner, with A4 = 440Hz.
+    ///
+    /// The frequency is rounded to the nearest semitone, and the pitch is spelled with a natural or a sharp.
+    /// A frequency exactly halfway between two semitones rounds away from A4.
+    /// This is the inverse of `to_hertz` for equal-tempered frequencies.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the pitch and the deviation of the frequency from it in cents, from -50 to 50,
+    /// e.g. (A4, 0.0) for 440Hz and (A4, about 3.9) for 441Hz,
+    /// or an error if the frequency is not a positive, finite number.
+    pub fn from_hertz(freq: f32) -> Result<(Pitch, f32), ()> {
+        if !freq.is_finite() || freq <= 0.0 {
+            return Err(());
+        }
+        let standard_pitch = Pitch::new_without_accidental(PitchName::A, 4);
+        let exact_semitones = 12.0 * (freq / 440.0).log2();
+        let number_of_semitones = exact_semitones.round();
+        let pitch = Pitch::try_from(f32::from(standard_pitch) + number_of_semitones * f32::from(IntervalStep::Half))?;
+        Ok((pitch, (exact_semitones - number_of_semitones) * 100.0))
+    }
+    /// Finds the equal-tempered pitch nearest to a frequency, ignoring how many cents the frequency deviates from it.
+    ///
+    /// See `from_hertz` for how the frequency is rounded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the frequency is not a positive, finite number.
+    pub fn from_hertz_snapped(freq: f32) -> Pitch {
+        match Pitch::from_hertz(freq) {
+            Ok((pitch, _)) => pitch,
+            Err(_) => panic!("{} Hz is not a valid frequency", freq),
+        }
+    }
+    /// Gets the MIDI note number of the pitch, from 0 (C-1) to 127 (G9), with 60 for middle C and 69 for A4.
+    ///
+    /// # Returns
+    ///
+    /// The note number, or `None` if the pitch is outside the MIDI range.
+    pub fn to_midi(&self) -> Option<u8> {
+        let number = f32::from(self.clone()) / f32::from(IntervalStep::Half) + 12.0;
+        if (0.0..=127.0).contains(&number) {
+            Some(number as u8)
+        } else {
+            None
+        }
+    }
+    /// Creates the pitch of a MIDI note number, e.g. A4 for 69.
+    ///
+    /// Black keys are spelled with a sharp.
+    pub fn from_midi(number: u8) -> Pitch {
+        Pitch::try_from((number as f32 - 12.0) * f32::from(IntervalStep::Half)).unwrap()
+    }
+    /// Writes the pitch with its fixed-do solfège syllable instead of its letter name, e.g. "Sol#4" for G#4.
+    pub fn to_solfege_string(&self) -> String {
+        format!("{}{}{}", self.name.to_solfege(), self.accidental, self.octave)
+    }
+    /// Parses a pitch in German naming, where H is B and B is B flat.
+    ///
+    /// Every sharp adds "is" and every flat adds "es" to the letter name (Fis, Ges, Cisis), except that
+    /// E and A only add "s" for their first flat (Es, As, Eses) and B double flat is Heses. The octave follows as usual, e.g. "Fis4".
+    /// Only the spellings written by `to_german_string` are accepted.
+    pub fn try_from_german(value: &str) -> Result<Pitch, ()> {
+        let captures = GERMAN_PITCH_REGEX.captures(value).ok_or(())?;
+        let letter = captures.get(1).ok_or(())?.as_str();
+        let suffix = captures.get(2).map_or("", |m| m.as_str());
+        let octave: i8 = captures.get(3).ok_or(())?.as_str().parse().map_err(|_| ())?;
+        let sharps = suffix.matches("is").count();
+        let flats = suffix.matches("s").count() - sharps;
+        let (name, flats) = match (letter, flats) {
+            ("B", 0) if sharps == 0 => (PitchName::B, 1),
+            ("B", _) => return Err(()),
+            // a single flat on H is written B
+            ("H", 1) => return Err(()),
+            ("H", _) => (PitchName::B, flats),
+            _ => (PitchName::try_from(letter.to_string())?, flats),
+        };
+        // E and A drop the "e" of their first flat, the other letters keep it
+        if flats > 0 && matches!(name, PitchName::E | PitchName::A) != suffix.starts_with('s') {
+            return Err(());
+        }
+        let accidental = match (sharps, flats) {
+            (0, 0) => Accidental::None,
+            (1, 0) => Accidental::Sharp,
+            (2, 0) => Accidental::DoubleSharp,
+            (3, 0) => Accidental::TripleSharp,
+            (0, 1) => Accidental::Flat,
+            (0, 2) => Accidental::DoubleFlat,
+            (0, 3) => Accidental::TripleFlat,
+            _ => return Err(()),
+        };
+        Ok(Pitch::new(name, octave, accidental))
+    }
+    /// Writes the pitch in German naming, e.g. "Fis4" for F#4, "H3" for B3 and "B3" for Bb3, see `try_from_german`.
+    pub fn to_german_string(&self) -> String {
+        let (sharps, flats) = match self.accidental {
+            Accidental::None => (0, 0),
+            Accidental::Sharp => (1, 0),
+            Accidental::DoubleSharp => (2, 0),
+            Accidental::TripleSharp => (3, 0),
+            Accidental::Flat => (0, 1),
+            Accidental::DoubleFlat => (0, 2),
+            Accidental::TripleFlat => (0, 3),
+        };
+        let name = match (&self.name, flats) {
+            (PitchName::B, 1) => "B".to_string(),
+            (PitchName::B, _) => format!("H{}{}", "is".repeat(sharps), "es".repeat(flats)),
+            (PitchName::E | PitchName::A, 1..) => format!("{}s{}", self.name, "es".repeat(flats - 1)),
+            _ => format!("{}{}{}", self.name, "is".repeat(sharps), "es".repeat(flats)),
+        };
+        format!("{}{}", name, self.octave)
     }
     pub fn distance(&self, other: &Self) -> f32 {
         let dist = f32::from(other.clone()) - f32::from(self.clone());
@@ -153,9 +417,148 @@ impl Pitch {
         }
         nearest_pitch
     }
+    /// Gets the pitch class of the pitch, keeping its spelling but dropping its octave (C for both C4 and C9).
+    pub fn pitch_class(&self) -> PitchClass {
+        PitchClass::from(self.clone())
+    }
+    /// Gets the vertical position of the notehead on a staff with the given clef, counted in lines and spaces from the bottom line.
+    ///
+    /// The bottom line is 0, the space above it 1 and the top line 8. Negative positions and positions above 8 need ledger lines,
+    /// e.g. middle C (C4) is -2 in the treble clef, on one ledger line below the staff. The accidental does not move the notehead.
+    pub fn staff_position(&self, clef: Clef) -> i32 {
+        let diatonic_index = |pitch: &Pitch| pitch.octave as i32 * 7 + pitch.name.position() as i32;
+        diatonic_index(self) - diatonic_index(&clef.bottom_line())
+    }
+    /// Checks whether the pitches share a pitch class regardless of their octaves, e.g. C3 and C5.
+    ///
+    /// Like `==`, enharmonic spellings are equivalent.
+    pub fn is_octave_equivalent(&self, other: &Pitch) -> bool {
+        self.pitch_class() == other.pitch_class()
+    }
+    /// Gets the tonic of the relative minor key of the major key on this pitch, a minor third below (A3 for C4).
+    ///
+    /// A sharp spelling is used when the diatonic spelling would need more than a double sharp or double flat.
+    pub fn relative_minor(&self) -> Pitch {
+        self.spell_at(-2, -3).unwrap_or_else(|_| self.transpose_semitones(-3))
+    }
+    /// Gets the tonic of the relative major key of the minor key on this pitch, a minor third above (C5 for A4).
+    ///
+    /// A sharp spelling is used when the diatonic spelling would need more than a double sharp or double flat.
+    pub fn relative_major(&self) -> Pitch {
+        self.spell_at(2, 3).unwrap_or_else(|_| self.transpose_semitones(3))
+    }
+    /// Finds the other pitch with the nearest pitch class, ignoring octaves.
+    ///
+    /// The distance is the shorter way around the octave, see `PitchClass::semitone_distance`, so B3 is as near to C5 as C#4.
+    /// Like `get_the_nearest_pitch`, the pitch itself is returned if there are no other pitches.
+    pub fn get_the_nearest_pitch_class(&self, others: Vec<Pitch>) -> Self {
+        let class = PitchClass::from(self.clone());
+        others.into_iter()
+            .min_by_key(|pitch| class.semitone_distance(&PitchClass::from(pitch.clone())))
+            .unwrap_or_else(|| self.clone())
+    }
+    /// Finds the nearest of the other pitches strictly above this pitch.
+    ///
+    /// Shifting the sample of that pitch down to this pitch avoids shifting up.
+    pub fn get_the_nearest_pitch_above(&self, others: Vec<Pitch>) -> Option<Self> {
+        others.into_iter().filter(|pitch| pitch > self).min()
+    }
+    /// Finds the nearest of the other pitches strictly below this pitch.
+    pub fn get_the_nearest_pitch_below(&self, others: Vec<Pitch>) -> Option<Self> {
+        others.into_iter().filter(|pitch| pitch < self).max()
+    }
+    /// Spells the pitch a number of letter names and semitones away from this pitch.
+    ///
+    /// # Arguments
+    ///
+    /// * `letter_steps` - The number of letter names to move, negative to move down
+    /// * `semitones` - The number of semitones the result should sound away from this pitch, negative to move down
+    ///
+    /// # Returns
+    ///
+    /// The pitch with the resulting letter name and the accidental needed to sound at the requested distance,
+    /// or an error if that would need more than a double sharp or double flat.
+    pub fn spell_at(&self, letter_steps: i16, semitones: i16) -> Result<Pitch, ()> {
+        let position = self.name.position() as i16 + self.octave as i16 * 7 + letter_steps;
+        let name = PitchName::from_position(position.rem_euclid(7) as u8);
+        let octave = i8::try_from(position.div_euclid(7)).map_err(|_| ())?;
+        let natural = Pitch::new_without_accidental(name.clone(), octave);
+        let target = f32::from(self.clone()) + semitones as f32 * f32::from(IntervalStep::Half);
+        let accidental = match target - f32::from(natural) {
+            -1.0 => Accidental::DoubleFlat,
+            -0.5 => Accidental::Flat,
+            0.0 => Accidental::None,
+            0.5 => Accidental::Sharp,
+            1.0 => Accidental::DoubleSharp,
+            _ => return Err(()),
+        };
+        Ok(Pitch::new(name, octave, accidental))
+    }
+    /// Counts the accidental signs of the pitch: 0 for a natural, 1 for a sharp or flat, 2 for a double and 3 for a triple sharp or flat.
+    ///
+    /// Of two spellings of the same pitch, the one with the lower count is simpler to read, see `simplify`.
+    pub fn accidental_complexity(&self) -> u8 {
+        match self.accidental {
+            Accidental::None => 0,
+            Accidental::Sharp | Accidental::Flat => 1,
+            Accidental::DoubleSharp | Accidental::DoubleFlat => 2,
+            Accidental::TripleSharp | Accidental::TripleFlat => 3,
+        }
+    }
+    /// Respells the pitch with as few accidentals as possible, keeping its sound (B#3 becomes C4, Fb4 becomes E4).
+    ///
+    /// A pitch that already has the fewest possible accidentals keeps its spelling.
+    pub fn simplify(&self) -> Pitch {
+        let mut simplest = self.clone();
+        for letter_steps in [-2, -1, 1, 2] {
+            if let Ok(pitch) = self.spell_at(letter_steps, 0) {
+                if pitch.accidental_complexity() < simplest.accidental_complexity() {
+                    simplest = pitch;
+                }
+            }
+        }
+        simplest
+    }
+    /// Lists the other spellings of the pitch with at most a double sharp or double flat, e.g. C##4 and Ebb4 for D4.
+    ///
+    /// All of them sound the same as the pitch, and the octave follows the letter name (B#3 for C4).
+    ///
+    /// # Returns
+    ///
+    /// The spellings in ascending letter order, without the spelling of the pitch itself.
+    pub fn enharmonic_equivalents(&self) -> Vec<Pitch> {
+        [-2, -1, 1, 2].into_iter().filter_map(|letter_steps| self.spell_at(letter_steps, 0).ok()).collect()
+    }
+    /// Transposes the pitch by an interval, keeping the spelling of the interval.
+    ///
+    /// The letter name moves by the interval number and the accidental follows from its size,
+    /// so C4 up a major third is E4 and up a minor third is Eb4 (not D#4), and compound intervals cross octaves.
+    /// A sharp spelling is used when this would need more than a double sharp or double flat.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The interval to move by
+    /// * `ascending` - Whether to move up or down
+    pub fn transpose(&self, interval: &Interval, ascending: bool) -> Pitch {
+        let direction = if ascending { 1 } else { -1 };
+        let letter_steps = (interval.get_number(false) as i16 - 1) * direction;
+        let semitones = interval.get_number_of_semitones(false) as i16 * direction;
+        self.spell_at(letter_steps, semitones).unwrap_or_else(|_| self.transpose_semitones(semitones as i32))
+    }
+    /// Transposes the pitch by a number of semitones.
+    ///
+    /// The letter name moves by the number of letters closest to the semitones, so the spelling follows the
+    /// transposition (C up 3 semitones is Eb, up 4 semitones is E), and octaves keep the spelling unchanged.
+    /// A sharp spelling is used when this would need more than a double sharp or double flat.
+    pub fn transpose_semitones(&self, semitones: i32) -> Pitch {
+        let letter_steps = (semitones as f32 * 7.0 / 12.0).round() as i16;
+        self.spell_at(letter_steps, semitones as i16).unwrap_or_else(|_| {
+            let value = f32::from(self.clone()) + semitones as f32 * f32::from(IntervalStep::Half);
+            Pitch::try_from(value).unwrap()
+        })
+    }
 }
 
-
 impl PartialEq<Self> for Pitch {
     fn eq(&self, other: &Self) -> bool {
         let left = f32::from(self.clone());
@@ -166,6 +569,42 @@ impl PartialEq<Self> for Pitch {
 
 impl Eq for Pitch {}
 
+impl Add<&Interval> for Pitch {
+    type Output = Pitch;
+
+    /// Transposes the pitch up by the interval, keeping the spelling of the interval, see `Pitch::transpose`.
+    fn add(self, rhs: &Interval) -> Self::Output {
+        self.transpose(rhs, true)
+    }
+}
+
+impl Add<&Interval> for &Pitch {
+    type Output = Pitch;
+
+    fn add(self, rhs: &Interval) -> Self::Output {
+        self.transpose(rhs, true)
+    }
+}
+
+impl Sub for Pitch {
+    type Output = Interval;
+
+    /// Builds the interval between the pitches, e.g. `e4 - c4` is a major third.
+    ///
+    /// Like `Interval::new`, the interval has no direction, so `c4 - e4` is the same major third.
+    fn sub(self, rhs: Self) -> Self::Output {
+        Interval::new(rhs, self)
+    }
+}
+
+impl Sub for &Pitch {
+    type Output = Interval;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Interval::new(rhs.clone(), self.clone())
+    }
+}
+
 impl PartialOrd<Self> for Pitch {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         let left = f32::from(self.clone());
@@ -222,6 +661,8 @@ impl From<Pitch> for f32 {
             Accidental::Flat => -0.5,
             Accidental::DoubleSharp => 1.0,
             Accidental::DoubleFlat => -1.0,
+            Accidental::TripleSharp => 1.5,
+            Accidental::TripleFlat => -1.5,
             Accidental::None => 0.0,
         };
         (pitch_value + accidental_value) + (value.octave as f32 * 6.0)
@@ -288,6 +729,8 @@ mod f32_from_pitch_tests {
         assert_eq!(f32::from(Pitch::new(PitchName::C, 0, Accidental::Flat)), -0.5);
         assert_eq!(f32::from(Pitch::new(PitchName::C, 0, Accidental::DoubleSharp)), 1.0);
         assert_eq!(f32::from(Pitch::new(PitchName::C, 0, Accidental::DoubleFlat)), -1.0);
+        assert_eq!(f32::from(Pitch::new(PitchName::C, 0, Accidental::TripleSharp)), 1.5);
+        assert_eq!(f32::from(Pitch::new(PitchName::C, 0, Accidental::TripleFlat)), -1.5);
     }
 }
 
@@ -365,6 +808,190 @@ mod to_hertz_tests {
         let pitch = Pitch::new_without_accidental(PitchName::A, -1);
         assert!(pitch.to_hertz() - 13.75 < 0.01);
     }
+
+    #[test]
+    fn test_with_reference() {
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        assert_eq!(a4.to_hertz_with_reference(442.0), 442.0);
+        assert_eq!(a4.to_hertz_with_reference(440.0), a4.to_hertz());
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert!((c4.to_hertz_with_reference(442.0) / c4.to_hertz() - 442.0 / 440.0).abs() < 1e-6);
+        assert!((c4.to_hertz_with_reference(442.0) - 262.81).abs() < 0.01);
+    }
+}
+
+#[cfg(test)]
+mod to_hertz_standard_tests {
+    use super::*;
+
+    #[test]
+    fn test_a4() {
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        assert_eq!(a4.to_hertz_standard(TuningStandard::ConcertA440), 440.0);
+        assert_eq!(a4.to_hertz_standard(TuningStandard::Baroque415), 415.0);
+        assert_eq!(a4.to_hertz_standard(TuningStandard::Classical430), 430.0);
+        assert_eq!(a4.to_hertz_standard(TuningStandard::Verdi432), 432.0);
+    }
+
+    #[test]
+    fn test_other_pitches() {
+        let a3 = Pitch::new_without_accidental(PitchName::A, 3);
+        assert_eq!(a3.to_hertz_standard(TuningStandard::Baroque415), 207.5);
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert_eq!(c4.to_hertz_standard(TuningStandard::ConcertA440), c4.to_hertz());
+    }
+}
+
+#[cfg(test)]
+mod from_hertz_tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_frequencies() {
+        assert_eq!(Pitch::from_hertz(440.0), Ok((Pitch::new_without_accidental(PitchName::A, 4), 0.0)));
+        let (c4, cents) = Pitch::from_hertz(261.63).unwrap();
+        assert_eq!(c4.to_string(), "C4");
+        assert!(cents.abs() < 0.1);
+    }
+
+    #[test]
+    fn test_cents_deviation() {
+        let (a4, cents) = Pitch::from_hertz(441.0).unwrap();
+        assert_eq!(a4.to_string(), "A4");
+        assert!((cents - 3.93).abs() < 0.01);
+        let (a4, cents) = Pitch::from_hertz(430.0).unwrap();
+        assert_eq!(a4.to_string(), "A4");
+        assert!((cents + 39.8).abs() < 0.1);
+        // a quarter tone above A4 is closer to A#4
+        let (a_sharp4, cents) = Pitch::from_hertz(440.0 * 2f32.powf(0.75 / 12.0)).unwrap();
+        assert_eq!(a_sharp4.to_string(), "A#4");
+        assert!((cents + 25.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_invalid_frequency() {
+        assert!(Pitch::from_hertz(0.0).is_err());
+        assert!(Pitch::from_hertz(-440.0).is_err());
+        assert!(Pitch::from_hertz(f32::NAN).is_err());
+        assert!(Pitch::from_hertz(f32::INFINITY).is_err());
+    }
+}
+
+#[cfg(test)]
+mod midi_tests {
+    use super::*;
+
+    #[test]
+    fn test_to_midi() {
+        assert_eq!(Pitch::new_without_accidental(PitchName::A, 4).to_midi(), Some(69));
+        assert_eq!(Pitch::new_without_accidental(PitchName::C, 4).to_midi(), Some(60));
+        assert_eq!(Pitch::new_without_accidental(PitchName::C, -1).to_midi(), Some(0));
+        assert_eq!(Pitch::new_without_accidental(PitchName::G, 9).to_midi(), Some(127));
+        assert_eq!(Pitch::new(PitchName::D, 4, Accidental::Flat).to_midi(), Some(61));
+        assert_eq!(Pitch::new(PitchName::B, 3, Accidental::Sharp).to_midi(), Some(60));
+    }
+
+    #[test]
+    fn test_to_midi_out_of_range() {
+        assert_eq!(Pitch::new(PitchName::C, -1, Accidental::Flat).to_midi(), None);
+        assert_eq!(Pitch::new(PitchName::G, 9, Accidental::Sharp).to_midi(), None);
+    }
+
+    #[test]
+    fn test_from_midi() {
+        assert_eq!(Pitch::from_midi(69).to_string(), "A4");
+        assert_eq!(Pitch::from_midi(60).to_string(), "C4");
+        assert_eq!(Pitch::from_midi(61).to_string(), "C#4");
+        assert_eq!(Pitch::from_midi(0).to_string(), "C-1");
+        assert_eq!(Pitch::from_midi(127).to_string(), "G9");
+        assert!((0..=127).all(|number| Pitch::from_midi(number).to_midi() == Some(number)));
+    }
+}
+
+#[cfg(test)]
+mod from_hertz_snapped_tests {
+    use super::*;
+
+    #[test]
+    fn test_snap() {
+        assert_eq!(Pitch::from_hertz_snapped(261.6).to_string(), "C4");
+        assert_eq!(Pitch::from_hertz_snapped(262.5).to_string(), "C4");
+        assert_eq!(Pitch::from_hertz_snapped(440.0).to_string(), "A4");
+        assert_eq!(Pitch::from_hertz_snapped(27.5).to_string(), "A0");
+        assert_eq!(Pitch::from_hertz_snapped(277.0).to_string(), "C#4");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let pitch = Pitch::new(PitchName::E, 5, Accidental::Flat);
+        assert_eq!(Pitch::from_hertz_snapped(pitch.to_hertz()), pitch);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_frequency() {
+        Pitch::from_hertz_snapped(0.0);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod pitch_serde_tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let pitch = Pitch::new(PitchName::C, 4, Accidental::Sharp);
+        let json = serde_json::to_string(&pitch).unwrap();
+        assert_eq!(json, "\"C#4\"");
+        let parsed: Pitch = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.to_string(), "C#4");
+        let accidental: Accidental = serde_json::from_str(&serde_json::to_string(&Accidental::DoubleFlat).unwrap()).unwrap();
+        assert_eq!(accidental, Accidental::DoubleFlat);
+    }
+
+    #[test]
+    fn test_invalid_name() {
+        assert!(serde_json::from_str::<Pitch>("\"H4\"").is_err());
+        assert!(serde_json::from_str::<Pitch>("4").is_err());
+    }
+}
+
+#[cfg(test)]
+mod add_tests {
+    use super::*;
+
+    #[test]
+    fn test_perfect_fifth() {
+        let fifth = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new_without_accidental(PitchName::G, 4));
+        assert_eq!((Pitch::new_without_accidental(PitchName::C, 4) + &fifth).to_string(), "G4");
+        assert_eq!((Pitch::new_without_accidental(PitchName::G, 4) + &fifth).to_string(), "D5");
+        assert_eq!((&Pitch::new_without_accidental(PitchName::B, 4) + &fifth).to_string(), "F#5");
+    }
+
+    #[test]
+    fn test_minor_seventh() {
+        let seventh = Interval::new(Pitch::new_without_accidental(PitchName::C, 4), Pitch::new(PitchName::B, 4, Accidental::Flat));
+        assert_eq!((Pitch::new_without_accidental(PitchName::D, 4) + &seventh).to_string(), "C5");
+        assert_eq!((Pitch::new_without_accidental(PitchName::E, 4) + &seventh).to_string(), "D5");
+        assert_eq!((&Pitch::new(PitchName::F, 4, Accidental::Sharp) + &seventh).to_string(), "E5");
+    }
+}
+
+#[cfg(test)]
+mod sub_tests {
+    use crate::theory::interval::IntervalQuality;
+    use super::*;
+
+    #[test]
+    fn test_sub() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let e4 = Pitch::new_without_accidental(PitchName::E, 4);
+        assert_eq!((e4.clone() - c4.clone()).get_specific_interval(), Ok((3, IntervalQuality::Major, false)));
+        assert_eq!((&e4 - &c4).get_specific_interval(), Ok((3, IntervalQuality::Major, false)));
+        assert_eq!((&c4 - &e4).get_specific_interval(), Ok((3, IntervalQuality::Major, false)));
+        let b_flat4 = Pitch::new(PitchName::B, 4, Accidental::Flat);
+        assert_eq!((&b_flat4 - &c4).get_specific_interval(), Ok((7, IntervalQuality::Minor, false)));
+    }
 }
 
 #[cfg(test)]
@@ -385,6 +1012,80 @@ mod cmp_tests {
     }
 }
 
+#[cfg(test)]
+mod solfege_tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_do_mapping() {
+        let syllables = ["Do", "Re", "Mi", "Fa", "Sol", "La", "Si"];
+        for (position, syllable) in syllables.iter().enumerate() {
+            let name = PitchName::from_position(position as u8);
+            assert_eq!(name.to_solfege(), *syllable);
+            assert_eq!(PitchName::from_solfege(syllable), Ok(name));
+        }
+        assert_eq!(PitchName::from_solfege("Ti"), Ok(PitchName::B));
+        assert_eq!(PitchName::from_solfege("SOL"), Ok(PitchName::G));
+        assert_eq!(PitchName::from_solfege("So"), Err(()));
+        assert_eq!(PitchName::from_solfege("C"), Err(()));
+    }
+
+    #[test]
+    fn test_to_solfege_string() {
+        assert_eq!(Pitch::new_without_accidental(PitchName::C, 4).to_solfege_string(), "Do4");
+        assert_eq!(Pitch::new(PitchName::G, 4, Accidental::Sharp).to_solfege_string(), "Sol#4");
+        assert_eq!(Pitch::new(PitchName::B, 3, Accidental::Flat).to_solfege_string(), "Sib3");
+        assert_eq!(Pitch::new(PitchName::F, -1, Accidental::DoubleSharp).to_solfege_string(), "Fa##-1");
+        // the letter names are unchanged
+        assert_eq!(Pitch::new(PitchName::G, 4, Accidental::Sharp).to_string(), "G#4");
+    }
+}
+
+#[cfg(test)]
+mod german_tests {
+    use super::*;
+
+    #[test]
+    fn test_h_and_b() {
+        assert_eq!(Pitch::try_from_german("H4").unwrap().to_string(), "B4");
+        assert_eq!(Pitch::try_from_german("B4").unwrap().to_string(), "Bb4");
+        assert_eq!(Pitch::try_from_german("His4").unwrap().to_string(), "B#4");
+        assert_eq!(Pitch::try_from_german("Heses4").unwrap().to_string(), "Bbb4");
+        assert!(Pitch::try_from_german("Hes4").is_err());
+        assert!(Pitch::try_from_german("Bes4").is_err());
+        // the English parsing is unchanged
+        assert_eq!(Pitch::try_from("B4").unwrap().to_string(), "B4");
+        assert!(Pitch::try_from("H4").is_err());
+    }
+
+    #[test]
+    fn test_suffixes() {
+        assert_eq!(Pitch::try_from_german("Fis4").unwrap().to_string(), "F#4");
+        assert_eq!(Pitch::try_from_german("Cisis3").unwrap().to_string(), "C##3");
+        assert_eq!(Pitch::try_from_german("Ges4").unwrap().to_string(), "Gb4");
+        assert_eq!(Pitch::try_from_german("Es4").unwrap().to_string(), "Eb4");
+        assert_eq!(Pitch::try_from_german("As-1").unwrap().to_string(), "Ab-1");
+        assert_eq!(Pitch::try_from_german("Eses4").unwrap().to_string(), "Ebb4");
+        assert!(Pitch::try_from_german("Ees4").is_err());
+        assert!(Pitch::try_from_german("Ds4").is_err());
+        assert!(Pitch::try_from_german("Fises4").is_err());
+        assert!(Pitch::try_from_german("Fis").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let names = ["C4", "C#4", "Db4", "Eb4", "Ebb4", "F##4", "Ab4", "B4", "Bb4", "Bbb4", "B#4", "Cbbb4", "A###4"];
+        for name in names {
+            let pitch = Pitch::try_from(name).unwrap();
+            let german = pitch.to_german_string();
+            assert_eq!(Pitch::try_from_german(&german).unwrap().to_string(), name, "{}", german);
+        }
+        assert_eq!(Pitch::try_from("Bb3").unwrap().to_german_string(), "B3");
+        assert_eq!(Pitch::try_from("B3").unwrap().to_german_string(), "H3");
+        assert_eq!(Pitch::try_from("Ab3").unwrap().to_german_string(), "As3");
+    }
+}
+
 #[cfg(test)]
 mod display_tests {
     use super::*;
@@ -445,6 +1146,191 @@ mod pitch_from_string_tests {
         let pitch = Pitch::try_from("Cbb0".to_string()).unwrap();
         assert_eq!(pitch, Pitch::new(PitchName::C, 0, Accidental::DoubleFlat));
     }
+
+    #[test]
+    fn test_c0_triple_accidentals() {
+        let pitch = Pitch::try_from("C###0".to_string()).unwrap();
+        assert_eq!(pitch.accidental, Accidental::TripleSharp);
+        assert_eq!(pitch.to_string(), "C###0");
+        assert_eq!(f32::from(pitch.clone()), 1.5);
+        assert_eq!(Pitch::try_from(f32::from(pitch)).unwrap().to_string(), "D#0");
+        let pitch = Pitch::try_from("Cbbb0".to_string()).unwrap();
+        assert_eq!(pitch.accidental, Accidental::TripleFlat);
+        assert_eq!(pitch.to_string(), "Cbbb0");
+        assert_eq!(f32::from(pitch.clone()), -1.5);
+        assert_eq!(Pitch::try_from(f32::from(pitch)).unwrap().to_string(), "A-1");
+        assert!(Pitch::try_from("C####0".to_string()).is_err());
+        assert!(Pitch::try_from("Cbbbb0".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_negative_octave() {
+        let pitch = Pitch::try_from("C-1".to_string()).unwrap();
+        assert_eq!(pitch, Pitch::new_without_accidental(PitchName::C, -1));
+        let pitch = Pitch::try_from("A-1".to_string()).unwrap();
+        assert_eq!(pitch.to_hertz(), 13.75);
+        let pitch = Pitch::try_from("G#-1".to_string()).unwrap();
+        assert_eq!(pitch, Pitch::new(PitchName::G, -1, Accidental::Sharp));
+        assert_eq!(pitch.to_string(), "G#-1");
+    }
+
+    #[test]
+    fn test_malformed_octave() {
+        for value in ["A--1", "A-", "A-0", "A-01", "A01", "A1-"] {
+            assert!(Pitch::try_from(value.to_string()).is_err(), "{}", value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod pitch_from_str_tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Pitch::try_from("C#4").unwrap().to_string(), "C#4");
+        assert_eq!(Pitch::try_from("Bbb10").unwrap().to_string(), "Bbb10");
+        assert_eq!(Pitch::try_from("A0"), Ok(Pitch::new_without_accidental(PitchName::A, 0)));
+        assert!(Pitch::try_from("H4").is_err());
+        assert!(Pitch::try_from("C").is_err());
+    }
+
+    #[test]
+    fn test_many() {
+        let names = ["C", "D", "E", "F", "G", "A", "B"];
+        let accidentals = ["", "#", "b", "##", "bb"];
+        for octave in 0..100 {
+            for name in names {
+                for accidental in accidentals {
+                    let value = format!("{}{}{}", name, accidental, octave);
+                    assert_eq!(Pitch::try_from(value.as_str()).unwrap().to_string(), value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_as_string() {
+        for value in ["C4", "F##2", "Gb7", "x"] {
+            assert_eq!(Pitch::try_from(value), Pitch::try_from(value.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod spell_at_tests {
+    use super::*;
+
+    #[test]
+    fn test_upwards() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert_eq!(format!("{}", c4.spell_at(2, 4).unwrap()), "E4");
+        assert_eq!(format!("{}", c4.spell_at(2, 3).unwrap()), "Eb4");
+        assert_eq!(format!("{}", c4.spell_at(1, 3).unwrap()), "D#4");
+        assert_eq!(format!("{}", c4.spell_at(7, 12).unwrap()), "C5");
+    }
+
+    #[test]
+    fn test_across_octave() {
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        assert_eq!(format!("{}", a4.spell_at(2, 4).unwrap()), "C#5");
+        let d4 = Pitch::new_without_accidental(PitchName::D, 4);
+        assert_eq!(format!("{}", d4.spell_at(-2, -3).unwrap()), "B3");
+    }
+
+    #[test]
+    fn test_too_many_accidentals() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert!(c4.spell_at(1, 5).is_err());
+    }
+}
+
+#[cfg(test)]
+mod accidental_complexity_tests {
+    use super::*;
+
+    #[test]
+    fn test_accidental_complexity() {
+        assert_eq!(Pitch::new_without_accidental(PitchName::C, 4).accidental_complexity(), 0);
+        assert_eq!(Pitch::new(PitchName::C, 4, Accidental::Sharp).accidental_complexity(), 1);
+        assert_eq!(Pitch::new(PitchName::D, 4, Accidental::Flat).accidental_complexity(), 1);
+        assert_eq!(Pitch::new(PitchName::C, 4, Accidental::DoubleSharp).accidental_complexity(), 2);
+        assert_eq!(Pitch::new(PitchName::E, 4, Accidental::DoubleFlat).accidental_complexity(), 2);
+    }
+}
+
+#[cfg(test)]
+mod simplify_tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify() {
+        assert_eq!(Pitch::new(PitchName::B, 3, Accidental::Sharp).simplify().to_string(), "C4");
+        assert_eq!(Pitch::new(PitchName::C, 4, Accidental::Flat).simplify().to_string(), "B3");
+        assert_eq!(Pitch::new(PitchName::F, 4, Accidental::Flat).simplify().to_string(), "E4");
+        assert_eq!(Pitch::new(PitchName::C, 4, Accidental::DoubleSharp).simplify().to_string(), "D4");
+        assert_eq!(Pitch::new(PitchName::E, 4, Accidental::DoubleSharp).simplify().to_string(), "F#4");
+    }
+
+    #[test]
+    fn test_already_simple() {
+        assert_eq!(Pitch::new(PitchName::B, 4, Accidental::Flat).simplify().to_string(), "Bb4");
+        assert_eq!(Pitch::new(PitchName::A, 4, Accidental::Sharp).simplify().to_string(), "A#4");
+        assert_eq!(Pitch::new_without_accidental(PitchName::E, 4).simplify().to_string(), "E4");
+    }
+}
+
+#[cfg(test)]
+mod enharmonic_equivalents_tests {
+    use crate::theory::test_helpers::names;
+    use super::*;
+
+    #[test]
+    fn test_natural() {
+        let d4 = Pitch::new_without_accidental(PitchName::D, 4);
+        let equivalents = d4.enharmonic_equivalents();
+        assert_eq!(names(&equivalents), vec!["C##4", "Ebb4"]);
+        assert!(equivalents.iter().all(|pitch| *pitch == d4));
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert_eq!(names(&c4.enharmonic_equivalents()), vec!["B#3", "Dbb4"]);
+    }
+
+    #[test]
+    fn test_sharp() {
+        let f_sharp4 = Pitch::new(PitchName::F, 4, Accidental::Sharp);
+        assert_eq!(names(&f_sharp4.enharmonic_equivalents()), vec!["E##4", "Gb4"]);
+        let g_flat4 = Pitch::new(PitchName::G, 4, Accidental::Flat);
+        assert_eq!(names(&g_flat4.enharmonic_equivalents()), vec!["E##4", "F#4"]);
+        let g_sharp4 = Pitch::new(PitchName::G, 4, Accidental::Sharp);
+        assert_eq!(names(&g_sharp4.enharmonic_equivalents()), vec!["Ab4"]);
+    }
+}
+
+#[cfg(test)]
+mod transpose_semitones_tests {
+    use super::*;
+
+    #[test]
+    fn test_spelling_follows_transposition() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert_eq!(c4.transpose_semitones(3).to_string(), "Eb4");
+        assert_eq!(c4.transpose_semitones(4).to_string(), "E4");
+        assert_eq!(c4.transpose_semitones(7).to_string(), "G4");
+        assert_eq!(c4.transpose_semitones(-1).to_string(), "B3");
+    }
+
+    #[test]
+    fn test_octaves_keep_spelling() {
+        let pitch = Pitch::new(PitchName::F, 4, Accidental::Sharp);
+        assert_eq!(pitch.transpose_semitones(12).to_string(), "F#5");
+        assert_eq!(pitch.transpose_semitones(-24).to_string(), "F#2");
+    }
+
+    #[test]
+    fn test_fallback_to_sharps() {
+        let pitch = Pitch::new(PitchName::B, 4, Accidental::DoubleSharp);
+        assert_eq!(pitch.transpose_semitones(2).to_string(), "D#5");
+    }
 }
 
 #[cfg(test)]
@@ -503,4 +1389,185 @@ mod get_the_nearest_pitch_tests {
         let pitches = vec![];
         assert_eq!(pitch.get_the_nearest_pitch(pitches), Pitch::new_without_accidental(PitchName::C, 0));
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod get_the_nearest_pitch_directional_tests {
+    use super::*;
+
+    fn candidates() -> Vec<Pitch> {
+        vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::A, 3),
+            Pitch::new_without_accidental(PitchName::G, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+        ]
+    }
+
+    #[test]
+    fn test_above() {
+        let pitch = Pitch::new(PitchName::D, 4, Accidental::Sharp);
+        assert_eq!(pitch.get_the_nearest_pitch_above(candidates()), Some(Pitch::new_without_accidental(PitchName::E, 4)));
+        // an equal pitch is not strictly above
+        let pitch = Pitch::new_without_accidental(PitchName::E, 4);
+        assert_eq!(pitch.get_the_nearest_pitch_above(candidates()), Some(Pitch::new_without_accidental(PitchName::G, 4)));
+        let pitch = Pitch::new_without_accidental(PitchName::B, 4);
+        assert_eq!(pitch.get_the_nearest_pitch_above(candidates()), None);
+    }
+
+    #[test]
+    fn test_below() {
+        let pitch = Pitch::new_without_accidental(PitchName::D, 4);
+        assert_eq!(pitch.get_the_nearest_pitch_below(candidates()), Some(Pitch::new_without_accidental(PitchName::C, 4)));
+        let pitch = Pitch::new_without_accidental(PitchName::C, 4);
+        assert_eq!(pitch.get_the_nearest_pitch_below(candidates()), Some(Pitch::new_without_accidental(PitchName::A, 3)));
+        let pitch = Pitch::new_without_accidental(PitchName::A, 3);
+        assert_eq!(pitch.get_the_nearest_pitch_below(candidates()), None);
+    }
+}
+
+#[cfg(test)]
+mod get_the_nearest_pitch_class_tests {
+    use super::*;
+
+    #[test]
+    fn test_other_octave() {
+        let pitch = Pitch::new_without_accidental(PitchName::C, 5);
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::A, 4),
+            Pitch::new_without_accidental(PitchName::B, 2),
+            Pitch::new_without_accidental(PitchName::E, 5),
+        ];
+        assert_eq!(pitch.get_the_nearest_pitch_class(pitches.clone()), Pitch::new_without_accidental(PitchName::B, 2));
+        // by sounding value A4 would be nearest
+        assert_eq!(pitch.get_the_nearest_pitch(pitches), Pitch::new_without_accidental(PitchName::A, 4));
+    }
+
+    #[test]
+    fn test_same_class() {
+        let pitch = Pitch::new(PitchName::F, 4, Accidental::Sharp);
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::D, 4),
+            Pitch::new(PitchName::G, 1, Accidental::Flat),
+        ];
+        assert_eq!(pitch.get_the_nearest_pitch_class(pitches).to_string(), "Gb1");
+        assert_eq!(pitch.get_the_nearest_pitch_class(vec![]), pitch);
+    }
+}
+
+#[cfg(test)]
+mod relative_tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_minor() {
+        assert_eq!(Pitch::new_without_accidental(PitchName::C, 4).relative_minor().to_string(), "A3");
+        assert_eq!(Pitch::new(PitchName::E, 4, Accidental::Flat).relative_minor().to_string(), "C4");
+        assert_eq!(Pitch::new_without_accidental(PitchName::D, 4).relative_minor().to_string(), "B3");
+    }
+
+    #[test]
+    fn test_relative_major() {
+        assert_eq!(Pitch::new_without_accidental(PitchName::A, 4).relative_major().to_string(), "C5");
+        assert_eq!(Pitch::new(PitchName::F, 4, Accidental::Sharp).relative_major().to_string(), "A4");
+        assert_eq!(Pitch::new_without_accidental(PitchName::E, 4).relative_major().to_string(), "G4");
+    }
+}
+
+#[cfg(test)]
+mod is_octave_equivalent_tests {
+    use super::*;
+
+    #[test]
+    fn test_is_octave_equivalent() {
+        let c3 = Pitch::new_without_accidental(PitchName::C, 3);
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let c5 = Pitch::new_without_accidental(PitchName::C, 5);
+        assert!(c3.is_octave_equivalent(&c5));
+        assert!(c4.is_octave_equivalent(&c4));
+        assert!(!c4.is_octave_equivalent(&Pitch::new_without_accidental(PitchName::D, 4)));
+        assert!(c4.is_octave_equivalent(&Pitch::new(PitchName::B, 6, Accidental::Sharp)));
+    }
+
+    #[test]
+    fn test_pitch_class() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let c9 = Pitch::new_without_accidental(PitchName::C, 9);
+        assert_eq!(c4.pitch_class(), c9.pitch_class());
+        assert_eq!(c9.pitch_class().to_string(), "C");
+        assert_eq!(Pitch::new(PitchName::D, 2, Accidental::Flat).pitch_class().chromatic_index(), 1);
+    }
+}
+
+#[cfg(test)]
+mod transpose_tests {
+    use super::*;
+
+    fn interval(lower: &str, upper: &str) -> Interval {
+        Interval::new(Pitch::try_from(lower).unwrap(), Pitch::try_from(upper).unwrap())
+    }
+
+    #[test]
+    fn test_spelling() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let major_third = interval("C4", "E4");
+        let minor_third = interval("A4", "C5");
+        assert_eq!(c4.transpose(&major_third, true).to_string(), "E4");
+        assert_eq!(c4.transpose(&minor_third, true).to_string(), "Eb4");
+        assert_eq!(c4.transpose(&minor_third, false).to_string(), "A3");
+    }
+
+    #[test]
+    fn test_augmented_and_diminished() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert_eq!(c4.transpose(&interval("F4", "B4"), true).to_string(), "F#4");
+        assert_eq!(c4.transpose(&interval("B3", "F4"), true).to_string(), "Gb4");
+        assert_eq!(c4.transpose(&interval("C4", "Fb4"), true).to_string(), "Fb4");
+        assert_eq!(Pitch::try_from("E4").unwrap().transpose(&interval("C4", "G#4"), true).to_string(), "B#4");
+        assert_eq!(Pitch::try_from("Eb4").unwrap().transpose(&interval("C4", "E4"), false).to_string(), "Cb4");
+    }
+
+    #[test]
+    fn test_octaves_and_compound_intervals() {
+        let b3 = Pitch::new_without_accidental(PitchName::B, 3);
+        assert_eq!(b3.transpose(&interval("C4", "D4"), true).to_string(), "C#4");
+        assert_eq!(b3.transpose(&interval("C4", "E5"), true).to_string(), "D#5");
+        assert_eq!(Pitch::try_from("G5").unwrap().transpose(&interval("C4", "A5"), false).to_string(), "Bb3");
+        assert_eq!(b3.transpose(&interval("C4", "C5"), false).to_string(), "B2");
+    }
+}
+
+#[cfg(test)]
+mod staff_position_tests {
+    use super::*;
+
+    fn position(pitch: &str, clef: Clef) -> i32 {
+        Pitch::try_from(pitch).unwrap().staff_position(clef)
+    }
+
+    #[test]
+    fn test_middle_c() {
+        assert_eq!(position("C4", Clef::Treble), -2);
+        assert_eq!(position("C4", Clef::Bass), 10);
+        assert_eq!(position("C4", Clef::Alto), 4);
+    }
+
+    #[test]
+    fn test_lines_and_spaces() {
+        assert_eq!(position("E4", Clef::Treble), 0);
+        assert_eq!(position("F4", Clef::Treble), 1);
+        assert_eq!(position("B4", Clef::Treble), 4);
+        assert_eq!(position("F5", Clef::Treble), 8);
+        assert_eq!(position("A5", Clef::Treble), 10);
+        assert_eq!(position("G2", Clef::Bass), 0);
+        assert_eq!(position("A3", Clef::Bass), 8);
+        assert_eq!(position("E2", Clef::Bass), -2);
+    }
+
+    #[test]
+    fn test_accidentals_do_not_move() {
+        assert_eq!(position("C#4", Clef::Treble), -2);
+        assert_eq!(position("Cb4", Clef::Treble), -2);
+        assert_eq!(position("B#3", Clef::Treble), -3);
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt::{Display, Formatter};
+use crate::theory::interval::IntervalStep;
+use crate::theory::pitch::{Accidental, Pitch, PitchName};
+use crate::utils::float_mod;
+
+/// A pitch without its octave, e.g. every C or every F#.
+#[derive(Clone, Debug)]
+pub struct PitchClass {
+    pub name: PitchName,
+    pub accidental: Accidental,
+}
+
+impl PitchClass {
+    pub fn new(name: PitchName, accidental: Accidental) -> Self {
+        Self { name, accidental }
+    }
+
+    /// The position of the pitch class within the chromatic octave, from 0 (C) to 11 (B).
+    ///
+    /// Enharmonic pitch classes share the same index, e.g. 1 for both C# and Db.
+    pub fn chromatic_index(&self) -> u8 {
+        (f32::from(self.clone()) / f32::from(IntervalStep::Half)) as u8
+    }
+
+    /// The number of semitones of the pitch class above C, from 0 to 11, the same as `chromatic_index`.
+    pub fn to_semitone(&self) -> u8 {
+        self.chromatic_index()
+    }
+
+    /// Calculates the number of semitones between two pitch classes, ignoring direction and octave.
+    ///
+    /// # Returns
+    ///
+    /// The shorter distance around the octave, from 0 to 6, e.g. 1 between B and C.
+    pub fn semitone_distance(&self, other: &PitchClass) -> u8 {
+        let distance = (self.chromatic_index() + 12 - other.chromatic_index()) % 12;
+        distance.min(12 - distance)
+    }
+}
+
+impl Display for PitchClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.name, self.accidental)
+    }
+}
+
+impl From<Pitch> for PitchClass {
+    fn from(value: Pitch) -> Self {
+        Self::new(value.name, value.accidental)
+    }
+}
+
+impl From<PitchClass> for f32 {
+    /// The value of the pitch class within an octave, from 0.0 (C) to 5.5 (B), see `From<Pitch> for f32`.
+    fn from(value: PitchClass) -> Self {
+        float_mod(f32::from(Pitch::new(value.name, 0, value.accidental)), 6.0)
+    }
+}
+
+impl PartialEq<Self> for PitchClass {
+    fn eq(&self, other: &Self) -> bool {
+        f32::from(self.clone()) == f32::from(other.clone())
+    }
+}
+
+impl Eq for PitchClass {}
+
+/// Collects the distinct pitch classes of the pitches, ignoring their octaves (C3, C4 and C5 are all C).
+///
+/// Enharmonic pitches count as the same pitch class, which keeps the spelling of its first occurrence.
+///
+/// # Returns
+///
+/// The pitch classes in ascending chromatic order, starting from C.
+pub fn unique_pitch_classes(pitches: &[Pitch]) -> Vec<PitchClass> {
+    let mut classes: Vec<PitchClass> = Vec::new();
+    for pitch in pitches {
+        let class = PitchClass::from(pitch.clone());
+        if !classes.contains(&class) {
+            classes.push(class);
+        }
+    }
+    classes.sort_by(|a, b| f32::from(a.clone()).total_cmp(&f32::from(b.clone())));
+    classes
+}
+
+/// Finds the most frequent pitch class of the pitches, ignoring their octaves, e.g. as a cheap guess of the tonic of a melody.
+///
+/// # Returns
+///
+/// The most frequent pitch class, spelled like its first occurrence, or `None` if there are no pitches.
+/// On a tie the pitch class with the lowest chromatic index wins, see `PitchClass::chromatic_index`.
+pub fn most_common_pitch_class(pitches: &[Pitch]) -> Option<PitchClass> {
+    let mut counts = [0usize; 12];
+    let mut spellings: [Option<PitchClass>; 12] = Default::default();
+    for pitch in pitches {
+        let class = PitchClass::from(pitch.clone());
+        let index = class.chromatic_index() as usize;
+        counts[index] += 1;
+        spellings[index].get_or_insert(class);
+    }
+    // the first maximum wins, i.e. the lowest chromatic index
+    let index = (0..12).rev().max_by_key(|index| counts[*index])?;
+    spellings[index].take()
+}
+
+#[cfg(test)]
+mod pitch_class_tests {
+    use super::*;
+
+    #[test]
+    fn test_from_pitch() {
+        let c4 = PitchClass::from(Pitch::new_without_accidental(PitchName::C, 4));
+        let c9 = PitchClass::from(Pitch::new_without_accidental(PitchName::C, 9));
+        assert_eq!(c4, c9);
+        assert_eq!(c4.to_string(), "C");
+    }
+
+    #[test]
+    fn test_enharmonic_eq() {
+        assert_eq!(PitchClass::new(PitchName::C, Accidental::Sharp), PitchClass::new(PitchName::D, Accidental::Flat));
+        assert_eq!(PitchClass::new(PitchName::B, Accidental::Sharp), PitchClass::new(PitchName::C, Accidental::None));
+        assert_ne!(PitchClass::new(PitchName::C, Accidental::None), PitchClass::new(PitchName::D, Accidental::None));
+    }
+}
+
+#[cfg(test)]
+mod chromatic_index_tests {
+    use super::*;
+
+    #[test]
+    fn test_chromatic_index() {
+        let naturals = [PitchName::C, PitchName::D, PitchName::E, PitchName::F, PitchName::G, PitchName::A, PitchName::B];
+        let indices: Vec<u8> = naturals.into_iter().map(|name| PitchClass::new(name, Accidental::None).chromatic_index()).collect();
+        assert_eq!(indices, vec![0, 2, 4, 5, 7, 9, 11]);
+        assert_eq!(PitchClass::new(PitchName::C, Accidental::Sharp).chromatic_index(), 1);
+        assert_eq!(PitchClass::new(PitchName::A, Accidental::Sharp).chromatic_index(), 10);
+    }
+
+    #[test]
+    fn test_enharmonics_agree() {
+        assert_eq!(PitchClass::new(PitchName::D, Accidental::Flat).chromatic_index(), 1);
+        assert_eq!(PitchClass::new(PitchName::B, Accidental::Sharp).chromatic_index(), 0);
+        assert_eq!(PitchClass::new(PitchName::C, Accidental::Flat).chromatic_index(), 11);
+        assert_eq!(PitchClass::new(PitchName::E, Accidental::DoubleSharp).chromatic_index(), 6);
+        assert_eq!(PitchClass::new(PitchName::C, Accidental::DoubleFlat).chromatic_index(), 10);
+    }
+
+    #[test]
+    fn test_to_semitone() {
+        assert_eq!(PitchClass::new(PitchName::C, Accidental::None).to_semitone(), 0);
+        assert_eq!(PitchClass::new(PitchName::D, Accidental::Flat).to_semitone(), 1);
+        assert_eq!(PitchClass::new(PitchName::B, Accidental::None).to_semitone(), 11);
+        assert_eq!(PitchClass::new(PitchName::B, Accidental::Sharp).to_semitone(), 0);
+    }
+
+    #[test]
+    fn test_semitone_distance() {
+        let c = PitchClass::new(PitchName::C, Accidental::None);
+        let b = PitchClass::new(PitchName::B, Accidental::None);
+        let f_sharp = PitchClass::new(PitchName::F, Accidental::Sharp);
+        assert_eq!(c.semitone_distance(&b), 1);
+        assert_eq!(b.semitone_distance(&c), 1);
+        assert_eq!(c.semitone_distance(&f_sharp), 6);
+        assert_eq!(c.semitone_distance(&PitchClass::new(PitchName::B, Accidental::Sharp)), 0);
+    }
+}
+
+#[cfg(test)]
+mod unique_pitch_classes_tests {
+    use super::*;
+
+    #[test]
+    fn test_collapse_octaves() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::G, 5),
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::E, 3),
+            Pitch::new_without_accidental(PitchName::C, 5),
+            Pitch::new_without_accidental(PitchName::G, 2),
+            Pitch::new_without_accidental(PitchName::C, 3),
+        ];
+        let classes: Vec<String> = unique_pitch_classes(&pitches).iter().map(|class| class.to_string()).collect();
+        assert_eq!(classes, vec!["C", "E", "G"]);
+    }
+
+    #[test]
+    fn test_enharmonic_keeps_first_spelling() {
+        let pitches = vec![
+            Pitch::new(PitchName::D, 4, Accidental::Flat),
+            Pitch::new(PitchName::C, 5, Accidental::Sharp),
+            Pitch::new_without_accidental(PitchName::B, 3),
+        ];
+        let classes: Vec<String> = unique_pitch_classes(&pitches).iter().map(|class| class.to_string()).collect();
+        assert_eq!(classes, vec!["Db", "B"]);
+        assert!(unique_pitch_classes(&[]).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod most_common_pitch_class_tests {
+    use super::*;
+
+    #[test]
+    fn test_dominated_by_c() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::C, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+            Pitch::new_without_accidental(PitchName::G, 4),
+            Pitch::new_without_accidental(PitchName::C, 5),
+            Pitch::new_without_accidental(PitchName::D, 5),
+            Pitch::new(PitchName::B, 4, Accidental::Sharp),
+        ];
+        let class = most_common_pitch_class(&pitches).unwrap();
+        assert_eq!(class, PitchClass::new(PitchName::C, Accidental::None));
+        assert_eq!(class.to_string(), "C");
+    }
+
+    #[test]
+    fn test_tie() {
+        let pitches = vec![
+            Pitch::new_without_accidental(PitchName::G, 4),
+            Pitch::new_without_accidental(PitchName::E, 4),
+        ];
+        assert_eq!(most_common_pitch_class(&pitches).unwrap().to_string(), "E");
+        assert_eq!(most_common_pitch_class(&[]), None);
+    }
+}
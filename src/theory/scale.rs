@@ -1,5 +1,94 @@
-use crate::theory::interval::IntervalStep;
+use crate::theory::interval::{semitones_of, Interval, IntervalQuality, IntervalStep};
+use crate::theory::pitch::{Accidental, Pitch};
+use crate::theory::pitch_class::PitchClass;
 
+/// Lists consecutive chromatic pitches, e.g. for drawing a keyboard.
+///
+/// # Arguments
+///
+/// * `start` - The first pitch
+/// * `count` - The number of pitches
+/// * `prefer_flats` - Whether black keys are spelled with flats (Db) instead of sharps (C#)
+///
+/// # Returns
+///
+/// `count` pitches a half step apart, ascending from `start`, each spelled with a natural where possible.
+pub fn chromatic_scale(start: Pitch, count: usize, prefer_flats: bool) -> Vec<Pitch> {
+    (0..count)
+        .map(|semitones| {
+            let value = f32::from(start.clone()) + semitones as f32 * f32::from(IntervalStep::Half);
+            let pitch = Pitch::try_from(value).unwrap();
+            if prefer_flats && pitch.accidental == Accidental::Sharp {
+                pitch.spell_at(1, 0).unwrap()
+            } else {
+                pitch
+            }
+        })
+        .collect()
+}
+
+/// Counts the pitch classes two scales share, each built on its own root (C major and G major share six).
+pub fn scale_common_tones(a: (&Scale, &Pitch), b: (&Scale, &Pitch)) -> usize {
+    let pitch_classes = |(scale, root): (&Scale, &Pitch)| -> Vec<u8> {
+        let root = PitchClass::from(root.clone()).chromatic_index();
+        scale.degree_offsets().iter().map(|offset| (root + offset) % 12).collect()
+    };
+    let a = pitch_classes(a);
+    pitch_classes(b).iter().filter(|class| a.contains(class)).count()
+}
+
+/// Scores how bright a scale sounds compared to the major scale.
+///
+/// Every degree raised by a half step above the same degree of the major scale adds 1 and every lowered degree subtracts 1,
+/// so the modes sort from Lydian (1) over Ionian (0) down to Locrian (-5). Only scales with seven degrees compare meaningfully.
+pub fn scale_brightness(scale: &Scale) -> i32 {
+    scale.degree_offsets()
+        .iter()
+        .zip(Scale::major().degree_offsets())
+        .map(|(offset, major)| *offset as i32 - major as i32)
+        .sum()
+}
+
+/// The frequency ratios of the degrees of the major scale above its tonic in 5-limit just intonation, from the tonic to the seventh.
+const JUST_MAJOR_RATIOS: [f32; 7] = [1.0, 9.0 / 8.0, 5.0 / 4.0, 4.0 / 3.0, 3.0 / 2.0, 5.0 / 3.0, 15.0 / 8.0];
+
+/// Calculates the frequency of a degree of a major scale tuned in 5-limit just intonation, e.g. the third at 5:4 above the tonic.
+///
+/// The tonic itself is tuned in equal temperament, see `Pitch::to_hertz_with_reference`, and the other degrees in pure ratios to it.
+/// Degrees above the seventh continue into the next octaves, so the eighth degree is the tonic an octave higher.
+///
+/// # Arguments
+///
+/// * `key_root` - The tonic of the major scale
+/// * `degree` - The degree, starting with 1 for the tonic
+/// * `reference` - The frequency of A4 in hertz
+///
+/// # Returns
+///
+/// The frequency in hertz, or an error if the degree is 0.
+pub fn just_degree_frequency(key_root: &Pitch, degree: usize, reference: f32) -> Result<f32, ()> {
+    if degree == 0 {
+        return Err(());
+    }
+    let octaves = ((degree - 1) / JUST_MAJOR_RATIOS.len()) as i32;
+    let ratio = JUST_MAJOR_RATIOS[(degree - 1) % JUST_MAJOR_RATIOS.len()];
+    Ok(key_root.to_hertz_with_reference(reference) * ratio * 2.0_f32.powi(octaves))
+}
+
+/// Whether the seventh degree of a scale leads up to the octave by a half step or lies a whole step below it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeventhDegreeKind {
+    LeadingTone,
+    Subtonic,
+}
+
+/// Whether a scale sounds major or minor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tonality {
+    Major,
+    Minor,
+    Ambiguous,
+}
 
 /// A scale is a collection of intervals that sum to 12.
 ///
@@ -8,6 +97,23 @@ pub struct Scale {
     steps: Vec<u8>,
 }
 
+/// Serializes the scale as its steps, e.g. `[2, 2, 1, 2, 2, 2, 1]` for the major scale.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Scale {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.steps)
+    }
+}
+
+/// Deserializes a scale from its steps, which must sum to 12 like in `Scale::try_new`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Scale {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let steps = Vec::<u8>::deserialize(deserializer)?;
+        Scale::try_new(steps).map_err(|_| serde::de::Error::custom("the steps of a scale must sum to 12"))
+    }
+}
+
 impl Scale {
     pub fn try_new<T>(steps: T) -> Result<Self, ()>
     where
@@ -21,10 +127,197 @@ impl Scale {
         }
         Ok(Self { steps })
     }
+
+    /// Creates a scale from the intervals between the root and each of the other degrees.
+    ///
+    /// # Arguments
+    ///
+    /// * `qualities` - The number and quality of the interval from the root to each degree above it,
+    ///   in ascending order and without the root itself, e.g. `(2, Major), (3, Major), (4, Perfect), ...`
+    pub fn from_qualities(qualities: &[(u8, IntervalQuality)]) -> Result<Self, ()> {
+        let mut steps = Vec::with_capacity(qualities.len() + 1);
+        let mut previous = 0;
+        for (number, quality) in qualities {
+            let semitones = semitones_of(*number, quality)?;
+            if semitones <= previous || semitones >= 12 {
+                return Err(());
+            }
+            steps.push(semitones - previous);
+            previous = semitones;
+        }
+        steps.push(12 - previous);
+        Self::try_new(steps)
+    }
+
+    /// The major scale.
+    pub fn major() -> Self {
+        Self { steps: vec![2, 2, 1, 2, 2, 2, 1] }
+    }
+
+    /// The natural minor scale.
+    pub fn natural_minor() -> Self {
+        Self { steps: vec![2, 1, 2, 2, 1, 2, 2] }
+    }
+
+    /// The harmonic minor scale, a natural minor scale with a raised 7th degree.
+    pub fn harmonic_minor() -> Self {
+        Self { steps: vec![2, 1, 2, 2, 1, 3, 1] }
+    }
+
+    /// The ascending melodic minor scale, a natural minor scale with raised 6th and 7th degrees.
+    pub fn melodic_minor() -> Self {
+        Self { steps: vec![2, 1, 2, 2, 2, 2, 1] }
+    }
+
+    /// Creates a mode of the major scale from its name, ignoring case, e.g. "Dorian".
+    ///
+    /// Accepts "ionian", "dorian", "phrygian", "lydian", "mixolydian", "aeolian" and "locrian",
+    /// and "major" and "minor" for the ionian and the aeolian mode.
+    pub fn from_mode_name(name: &str) -> Result<Self, ()> {
+        let rotation = match name.to_lowercase().as_str() {
+            "ionian" | "major" => 0,
+            "dorian" => 1,
+            "phrygian" => 2,
+            "lydian" => 3,
+            "mixolydian" => 4,
+            "aeolian" | "minor" => 5,
+            "locrian" => 6,
+            _ => return Err(()),
+        };
+        let mut steps = Self::major().steps;
+        steps.rotate_left(rotation);
+        Ok(Self { steps })
+    }
+
+    /// Calculates the offset of each degree from the root.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` with the number of half steps between the root and each degree, starting with 0 for the root.
+    pub fn degree_offsets(&self) -> Vec<u8> {
+        let mut offset = 0;
+        let mut offsets = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            offsets.push(offset);
+            offset += step;
+        }
+        offsets
+    }
+
+    /// Gets the pitch of a degree of the scale built on a root.
+    ///
+    /// Degrees of seven-note scales use consecutive letter names, other scales use the letter name closest to the degree.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The root of the scale
+    /// * `degree` - The degree, starting with 1 for the root. Degrees beyond the scale continue in the next octaves.
+    pub fn degree(&self, root: &Pitch, degree: usize) -> Result<Pitch, ()> {
+        if degree == 0 || self.steps.is_empty() {
+            return Err(());
+        }
+        let offsets = self.degree_offsets();
+        let octaves = ((degree - 1) / offsets.len()) as i16;
+        let offset = offsets[(degree - 1) % offsets.len()] as i16;
+        let letter_steps = if offsets.len() == 7 {
+            (degree - 1) as i16
+        } else {
+            (offset as f32 * 7.0 / 12.0).round() as i16 + octaves * 7
+        };
+        root.spell_at(letter_steps, offset + octaves * 12)
+    }
+
+    /// Gets the pitches of the scale built on a root, from the root upwards, see `degree`.
+    ///
+    /// A scale with seven degrees uses every letter name once, so raised and lowered degrees keep their letters
+    /// (G# rather than Ab in A harmonic minor).
+    pub fn pitches(&self, root: &Pitch) -> Result<Vec<Pitch>, ()> {
+        (1..=self.steps.len()).map(|degree| self.degree(root, degree)).collect()
+    }
+
+    /// Gets the pitches of the harmonic minor scale built on a root, with its raised seventh degree.
+    pub fn harmonic_minor_pitches(root: &Pitch) -> Result<Vec<Pitch>, ()> {
+        Self::harmonic_minor().pitches(root)
+    }
+
+    /// Gets the pitches of the ascending melodic minor scale built on a root, with its raised sixth and seventh degrees.
+    pub fn melodic_minor_pitches(root: &Pitch) -> Result<Vec<Pitch>, ()> {
+        Self::melodic_minor().pitches(root)
+    }
+
+    /// Gets the interval between two degrees of the scale built on a root.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The root of the scale
+    /// * `from` - The first degree, starting with 1 for the root
+    /// * `to` - The second degree, starting with 1 for the root
+    pub fn interval_between_degrees(&self, root: &Pitch, from: usize, to: usize) -> Result<Interval, ()> {
+        Ok(Interval::new(self.degree(root, from)?, self.degree(root, to)?))
+    }
+
+    /// Gets the pitch classes that are not part of the scale built on a root, spelled with sharps.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The root of the scale
+    ///
+    /// # Returns
+    ///
+    /// The missing pitch classes in ascending order from the root, e.g. C#, D#, F#, G#, A# for C major.
+    pub fn complement(&self, root: &Pitch) -> Vec<PitchClass> {
+        let offsets = self.degree_offsets();
+        (0..12u8)
+            .filter(|semitones| !offsets.contains(semitones))
+            .map(|semitones| {
+                let value = f32::from(root.clone()) + f32::from(semitones) * f32::from(IntervalStep::Half);
+                PitchClass::from(Pitch::try_from(value).unwrap())
+            })
+            .collect()
+    }
+
+    /// Classifies the seventh degree of the scale built on a root.
+    ///
+    /// # Returns
+    ///
+    /// `SeventhDegreeKind::LeadingTone` if the seventh degree lies a half step below the octave (major, harmonic minor),
+    /// `SeventhDegreeKind::Subtonic` if it lies a whole step below (Mixolydian, Dorian, natural minor),
+    /// or an error if the scale does not have seven degrees or its seventh degree lies further below the octave.
+    pub fn seventh_degree_kind(&self, root: &Pitch) -> Result<SeventhDegreeKind, ()> {
+        if self.steps.len() != 7 {
+            return Err(());
+        }
+        let seventh = self.degree(root, 7)?;
+        let octave = self.degree(root, 8)?;
+        match Interval::new(seventh, octave).get_number_of_semitones(false) {
+            1 => Ok(SeventhDegreeKind::LeadingTone),
+            2 => Ok(SeventhDegreeKind::Subtonic),
+            _ => Err(()),
+        }
+    }
+
+    /// Classifies the scale as major or minor by its third degree.
+    ///
+    /// # Returns
+    ///
+    /// `Tonality::Major` if the third degree lies 4 half steps above the root, `Tonality::Minor` if it lies 3 half steps above,
+    /// or `Tonality::Ambiguous` otherwise and for scales without seven degrees, e.g. the whole-tone scale.
+    pub fn tonality(&self) -> Tonality {
+        let offsets = self.degree_offsets();
+        if offsets.len() != 7 {
+            return Tonality::Ambiguous;
+        }
+        match offsets[2] {
+            4 => Tonality::Major,
+            3 => Tonality::Minor,
+            _ => Tonality::Ambiguous,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::theory::pitch::PitchName;
     use super::*;
 
     #[test]
@@ -34,4 +327,217 @@ mod tests {
         ]);
         assert!(scale.is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_from_qualities() {
+        let scale = Scale::from_qualities(&[
+            (2, IntervalQuality::Major),
+            (3, IntervalQuality::Major),
+            (4, IntervalQuality::Perfect),
+            (5, IntervalQuality::Perfect),
+            (6, IntervalQuality::Major),
+            (7, IntervalQuality::Major),
+        ]).unwrap();
+        assert_eq!(scale.degree_offsets(), Scale::major().degree_offsets());
+    }
+
+    #[test]
+    fn test_from_qualities_invalid() {
+        assert!(Scale::from_qualities(&[(3, IntervalQuality::Perfect)]).is_err());
+        assert!(Scale::from_qualities(&[(3, IntervalQuality::Major), (2, IntervalQuality::Major)]).is_err());
+        assert!(Scale::from_qualities(&[(8, IntervalQuality::Perfect)]).is_err());
+    }
+
+    #[test]
+    fn test_degree() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        assert_eq!(format!("{}", Scale::major().degree(&c4, 1).unwrap()), "C4");
+        assert_eq!(format!("{}", Scale::major().degree(&c4, 7).unwrap()), "B4");
+        assert_eq!(format!("{}", Scale::major().degree(&c4, 9).unwrap()), "D5");
+        assert_eq!(format!("{}", Scale::natural_minor().degree(&c4, 3).unwrap()), "Eb4");
+        let d4 = Pitch::new_without_accidental(PitchName::D, 4);
+        assert_eq!(format!("{}", Scale::major().degree(&d4, 3).unwrap()), "F#4");
+        assert!(Scale::major().degree(&c4, 0).is_err());
+    }
+
+    #[test]
+    fn test_interval_between_degrees() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let interval = Scale::major().interval_between_degrees(&c4, 1, 3).unwrap();
+        assert_eq!(interval.get_specific_interval(), Ok((3, IntervalQuality::Major, false)));
+        let interval = Scale::major().interval_between_degrees(&c4, 2, 5).unwrap();
+        assert_eq!(interval.get_specific_interval(), Ok((4, IntervalQuality::Perfect, false)));
+    }
+
+    #[test]
+    fn test_complement() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let complement: Vec<String> = Scale::major().complement(&c4).iter().map(|class| class.to_string()).collect();
+        assert_eq!(complement, vec!["C#", "D#", "F#", "G#", "A#"]);
+
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        let complement: Vec<String> = Scale::natural_minor().complement(&a4).iter().map(|class| class.to_string()).collect();
+        assert_eq!(complement, vec!["A#", "C#", "D#", "F#", "G#"]);
+    }
+
+    #[test]
+    fn test_degree_offsets() {
+        assert_eq!(Scale::major().degree_offsets(), vec![0, 2, 4, 5, 7, 9, 11]);
+        assert_eq!(Scale::natural_minor().degree_offsets(), vec![0, 2, 3, 5, 7, 8, 10]);
+    }
+
+    #[test]
+    fn test_from_mode_name() {
+        let dorian = Scale::from_mode_name("dorian").unwrap();
+        assert_eq!(dorian.degree_offsets(), vec![0, 2, 3, 5, 7, 9, 10]);
+        assert_eq!(Scale::from_mode_name("Locrian").unwrap().degree_offsets(), vec![0, 1, 3, 5, 6, 8, 10]);
+        assert_eq!(Scale::from_mode_name("LYDIAN").unwrap().degree_offsets(), vec![0, 2, 4, 6, 7, 9, 11]);
+        assert_eq!(Scale::from_mode_name("major").unwrap().degree_offsets(), Scale::major().degree_offsets());
+        assert_eq!(Scale::from_mode_name("Minor").unwrap().degree_offsets(), Scale::natural_minor().degree_offsets());
+        assert!(Scale::from_mode_name("blues").is_err());
+        assert!(Scale::from_mode_name("").is_err());
+    }
+
+    #[test]
+    fn test_tonality() {
+        let lydian = Scale::try_new(vec![2, 2, 2, 1, 2, 2, 1]).unwrap();
+        let mixolydian = Scale::try_new(vec![2, 2, 1, 2, 2, 1, 2]).unwrap();
+        assert_eq!(Scale::major().tonality(), Tonality::Major);
+        assert_eq!(lydian.tonality(), Tonality::Major);
+        assert_eq!(mixolydian.tonality(), Tonality::Major);
+
+        let dorian = Scale::try_new(vec![2, 1, 2, 2, 2, 1, 2]).unwrap();
+        let phrygian = Scale::try_new(vec![1, 2, 2, 2, 1, 2, 2]).unwrap();
+        assert_eq!(dorian.tonality(), Tonality::Minor);
+        assert_eq!(Scale::natural_minor().tonality(), Tonality::Minor);
+        assert_eq!(phrygian.tonality(), Tonality::Minor);
+
+        let whole_tone = Scale::try_new(vec![2, 2, 2, 2, 2, 2]).unwrap();
+        assert_eq!(whole_tone.tonality(), Tonality::Ambiguous);
+    }
+
+    #[test]
+    fn test_chromatic_scale() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let sharps: Vec<String> = chromatic_scale(c4.clone(), 13, false).iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(sharps, vec!["C4", "C#4", "D4", "D#4", "E4", "F4", "F#4", "G4", "G#4", "A4", "A#4", "B4", "C5"]);
+        let flats: Vec<String> = chromatic_scale(c4, 13, true).iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(flats, vec!["C4", "Db4", "D4", "Eb4", "E4", "F4", "Gb4", "G4", "Ab4", "A4", "Bb4", "B4", "C5"]);
+
+        let b_sharp = Pitch::new(PitchName::B, 3, Accidental::Sharp);
+        let respelled: Vec<String> = chromatic_scale(b_sharp, 2, true).iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(respelled, vec!["C4", "Db4"]);
+        assert!(chromatic_scale(Pitch::new_without_accidental(PitchName::A, 4), 0, false).is_empty());
+    }
+
+    #[test]
+    fn test_scale_brightness() {
+        let modes = [
+            vec![2, 2, 2, 1, 2, 2, 1], // Lydian
+            vec![2, 2, 1, 2, 2, 2, 1], // Ionian
+            vec![2, 2, 1, 2, 2, 1, 2], // Mixolydian
+            vec![2, 1, 2, 2, 2, 1, 2], // Dorian
+            vec![2, 1, 2, 2, 1, 2, 2], // Aeolian
+            vec![1, 2, 2, 2, 1, 2, 2], // Phrygian
+            vec![1, 2, 2, 1, 2, 2, 2], // Locrian
+        ];
+        let scores: Vec<i32> = modes.into_iter().map(|steps| scale_brightness(&Scale::try_new(steps).unwrap())).collect();
+        assert_eq!(scores, vec![1, 0, -1, -2, -3, -4, -5]);
+        assert!(scale_brightness(&Scale::harmonic_minor()) > scale_brightness(&Scale::natural_minor()));
+    }
+
+    #[test]
+    fn test_seventh_degree_kind() {
+        let g4 = Pitch::new_without_accidental(PitchName::G, 4);
+        let mixolydian = Scale::try_new(vec![2, 2, 1, 2, 2, 1, 2]).unwrap();
+        let dorian = Scale::try_new(vec![2, 1, 2, 2, 2, 1, 2]).unwrap();
+        assert_eq!(Scale::major().seventh_degree_kind(&g4), Ok(SeventhDegreeKind::LeadingTone));
+        assert_eq!(Scale::harmonic_minor().seventh_degree_kind(&g4), Ok(SeventhDegreeKind::LeadingTone));
+        assert_eq!(mixolydian.seventh_degree_kind(&g4), Ok(SeventhDegreeKind::Subtonic));
+        assert_eq!(dorian.seventh_degree_kind(&g4), Ok(SeventhDegreeKind::Subtonic));
+        assert_eq!(Scale::natural_minor().seventh_degree_kind(&g4), Ok(SeventhDegreeKind::Subtonic));
+
+        let whole_tone = Scale::try_new(vec![2, 2, 2, 2, 2, 2]).unwrap();
+        assert!(whole_tone.seventh_degree_kind(&g4).is_err());
+    }
+
+    #[test]
+    fn test_minor_pitches() {
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        let harmonic: Vec<String> = Scale::harmonic_minor_pitches(&a4).unwrap().iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(harmonic, vec!["A4", "B4", "C5", "D5", "E5", "F5", "G#5"]);
+        let melodic: Vec<String> = Scale::melodic_minor_pitches(&a4).unwrap().iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(melodic, vec!["A4", "B4", "C5", "D5", "E5", "F#5", "G#5"]);
+
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let harmonic: Vec<String> = Scale::harmonic_minor_pitches(&c4).unwrap().iter().map(|pitch| pitch.to_string()).collect();
+        assert_eq!(harmonic, vec!["C4", "D4", "Eb4", "F4", "G4", "Ab4", "B4"]);
+    }
+
+    #[test]
+    fn test_scale_common_tones() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let g3 = Pitch::new_without_accidental(PitchName::G, 3);
+        let f_sharp = Pitch::new(PitchName::F, 4, Accidental::Sharp);
+        let major = Scale::major();
+        assert_eq!(scale_common_tones((&major, &c4), (&major, &g3)), 6);
+        assert_eq!(scale_common_tones((&major, &c4), (&major, &f_sharp)), 2);
+        assert_eq!(scale_common_tones((&major, &c4), (&major, &c4)), 7);
+        let a_minor = Pitch::new_without_accidental(PitchName::A, 2);
+        assert_eq!(scale_common_tones((&major, &c4), (&Scale::natural_minor(), &a_minor)), 7);
+    }
+}
+
+#[cfg(test)]
+mod just_degree_frequency_tests {
+    use crate::theory::pitch::PitchName;
+    use super::*;
+
+    /// The distance between two frequencies in cents.
+    fn cents(from: f32, to: f32) -> f32 {
+        1200.0 * (to / from).log2()
+    }
+
+    #[test]
+    fn test_against_equal_temperament() {
+        let c4 = Pitch::new_without_accidental(PitchName::C, 4);
+        let third = just_degree_frequency(&c4, 3, 440.0).unwrap();
+        assert!((third / c4.to_hertz() - 1.25).abs() < 1e-6);
+        // the just major third is about 13.7 cents flatter than the equal-tempered one
+        assert!((cents(Pitch::new_without_accidental(PitchName::E, 4).to_hertz(), third) + 13.69).abs() < 0.01);
+        // the just fifth is about 2 cents sharper
+        let fifth = just_degree_frequency(&c4, 5, 440.0).unwrap();
+        assert!((cents(Pitch::new_without_accidental(PitchName::G, 4).to_hertz(), fifth) - 1.96).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_tonic_and_octaves() {
+        let a4 = Pitch::new_without_accidental(PitchName::A, 4);
+        assert_eq!(just_degree_frequency(&a4, 1, 440.0), Ok(440.0));
+        assert_eq!(just_degree_frequency(&a4, 8, 440.0), Ok(880.0));
+        assert_eq!(just_degree_frequency(&a4, 10, 432.0), Ok(432.0 * 2.0 * 1.25));
+    }
+
+    #[test]
+    fn test_degree_zero() {
+        assert_eq!(just_degree_frequency(&Pitch::new_without_accidental(PitchName::C, 4), 0, 440.0), Err(()));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let json = serde_json::to_string(&Scale::major()).unwrap();
+        assert_eq!(json, "[2,2,1,2,2,2,1]");
+        let parsed: Scale = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.degree_offsets(), Scale::major().degree_offsets());
+    }
+
+    #[test]
+    fn test_invalid_steps() {
+        assert!(serde_json::from_str::<Scale>("[2,2,1]").is_err());
+    }
+}
@@ -0,0 +1,6 @@
+use crate::theory::pitch::Pitch;
+
+/// Writes the pitches by name, e.g. "C#4", to compare them in assertions.
+pub fn names(pitches: &[Pitch]) -> Vec<String> {
+    pitches.iter().map(|pitch| pitch.to_string()).collect()
+}